@@ -1,7 +1,4 @@
-use chrono::{Duration, Utc};
-use rhorizons::{
-    ephemeris_orbital_elements_si, major_bodies, EphemerisOrbitalElementsItem, SiUnits,
-};
+use rhorizons::{major_bodies, EphemerisOrbitalElementsItem, EphemerisQuery, SiUnits, TimeSpec};
 
 #[tokio::main]
 async fn main() {
@@ -16,16 +13,15 @@ async fn main() {
 
     println!("Found Mars's Horizons identifier: {}.", earth.id);
 
-    let start_time = Utc::now() - Duration::days(1);
-    let stop_time = Utc::now();
+    let query = EphemerisQuery::new(earth.id, TimeSpec::last_day());
 
     println!(
         "Mars's orbital elements from {} to {}:",
-        start_time, stop_time
+        query.time.start, query.time.stop
     );
 
     let elements: Vec<EphemerisOrbitalElementsItem<f32, SiUnits>> =
-        ephemeris_orbital_elements_si(earth.id, start_time, stop_time).await;
+        query.orbital_elements_si().await.items;
     for item in elements {
         println!(
             "Eccentricity: {:?}, Semi-major axis: {:?}, Inclination: {:?}, Longitude of ascending node: {:?}, Argument of perifocus: {:?}, Mean anomaly: {:?}",
@@ -1,5 +1,4 @@
-use chrono::{Duration, Utc};
-use rhorizons::{ephemeris_vector, major_bodies, DefaultUnits, EphemerisVectorItem};
+use rhorizons::{major_bodies, DefaultUnits, EphemerisQuery, EphemerisVectorItem, TimeSpec};
 
 #[tokio::main]
 async fn main() {
@@ -14,16 +13,14 @@ async fn main() {
 
     println!("Found Earth's Horizons identifier: {}.", earth.id);
 
-    let start_time = Utc::now() - Duration::days(1);
-    let stop_time = Utc::now();
+    let query = EphemerisQuery::new(earth.id, TimeSpec::last_day());
 
     println!(
         "Earth's positions and velocities from {} to {}:",
-        start_time, stop_time
+        query.time.start, query.time.stop
     );
 
-    let vectors: Vec<EphemerisVectorItem<f32, DefaultUnits>> =
-        ephemeris_vector(earth.id, start_time, stop_time).await;
+    let vectors: Vec<EphemerisVectorItem<f32, DefaultUnits>> = query.vectors().await.items;
 
     for item in vectors {
         println!(
@@ -0,0 +1,380 @@
+use chrono::{DateTime, Utc};
+use num_traits::Float;
+
+use crate::{units::DefaultUnits, EphemerisVectorItem};
+
+/// Seconds elapsed between two timestamps.
+fn seconds_between<F: Float>(from: DateTime<Utc>, to: DateTime<Utc>) -> F {
+    F::from((to - from).num_milliseconds()).unwrap() / F::from(1000).unwrap()
+}
+
+/// Estimate acceleration (in km/s²) of the body at each entry of the
+/// ephemeris, by finite differences of the velocity.
+///
+/// Every entry uses a three-point, second order difference, which also works
+/// when the entries are not evenly spaced: central for interior entries and
+/// one-sided for the first and last ones. With only two entries, both get the
+/// same first order difference.
+///
+/// Entries must be strictly increasing in time and there needs to be at least
+/// two of them, otherwise the result is empty.
+pub fn estimate_accelerations<F: Float>(
+    items: &[EphemerisVectorItem<F, DefaultUnits>],
+) -> Vec<[F; 3]> {
+    if items.len() < 2 || items.windows(2).any(|pair| pair[0].time >= pair[1].time) {
+        return Vec::new();
+    }
+
+    if let [first, last] = items {
+        let h = seconds_between::<F>(first.time, last.time);
+        let acceleration = std::array::from_fn(|i| (last.velocity[i] - first.velocity[i]) / h);
+        return vec![acceleration; 2];
+    }
+
+    // Three consecutive entries and the two time steps between them.
+    let window = |start: usize| {
+        let window: &[_; 3] = items[start..start + 3].try_into().unwrap();
+        let h1 = seconds_between::<F>(window[0].time, window[1].time);
+        let h2 = seconds_between::<F>(window[1].time, window[2].time);
+        (window, h1, h2)
+    };
+    let two = F::one() + F::one();
+
+    let mut accelerations = Vec::with_capacity(items.len());
+
+    let ([a, b, c], h1, h2) = window(0);
+    accelerations.push(std::array::from_fn(|i| {
+        -(two * h1 + h2) / (h1 * (h1 + h2)) * a.velocity[i] + (h1 + h2) / (h1 * h2) * b.velocity[i]
+            - h1 / (h2 * (h1 + h2)) * c.velocity[i]
+    }));
+
+    for start in 0..items.len() - 2 {
+        let ([a, b, c], h1, h2) = window(start);
+        accelerations.push(std::array::from_fn(|i| {
+            -h2 / (h1 * (h1 + h2)) * a.velocity[i]
+                + (h2 - h1) / (h1 * h2) * b.velocity[i]
+                + h1 / (h2 * (h1 + h2)) * c.velocity[i]
+        }));
+    }
+
+    let ([a, b, c], h1, h2) = window(items.len() - 3);
+    accelerations.push(std::array::from_fn(|i| {
+        h2 / (h1 * (h1 + h2)) * a.velocity[i] - (h1 + h2) / (h1 * h2) * b.velocity[i]
+            + (h1 + two * h2) / (h2 * (h1 + h2)) * c.velocity[i]
+    }));
+
+    accelerations
+}
+
+/// Difference between two ephemerides of the same body at a single epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochDifference<F: Float> {
+    /// Timestamp of the epoch in UTC
+    pub time: DateTime<Utc>,
+    /// Position in km of the second ephemeris minus the first one
+    pub position: [F; 3],
+    /// Velocity in km/s of the second ephemeris minus the first one
+    pub velocity: [F; 3],
+}
+
+impl<F: Float> EpochDifference<F> {
+    /// Distance in km between the positions.
+    pub fn position_deviation(&self) -> F {
+        magnitude(self.position)
+    }
+
+    /// Magnitude in km/s of the difference between the velocities.
+    pub fn velocity_deviation(&self) -> F {
+        magnitude(self.velocity)
+    }
+}
+
+/// Result of [`compare_ephemerides`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EphemerisDifference<F: Float> {
+    /// Differences at epochs present in both ephemerides
+    pub epochs: Vec<EpochDifference<F>>,
+    /// Largest distance in km between the positions, zero if there are no
+    /// common epochs
+    pub max_position_deviation: F,
+    /// Largest velocity difference in km/s, zero if there are no common epochs
+    pub max_velocity_deviation: F,
+}
+
+fn magnitude<F: Float>(vector: [F; 3]) -> F {
+    vector
+        .into_iter()
+        .fold(F::zero(), |sum, value| sum + value * value)
+        .sqrt()
+}
+
+/// Compare two ephemerides of the same body relative to the same center, e.g.
+/// fetched months apart or with different settings, to validate caches or
+/// ephemeris updates.
+///
+/// Only epochs present in both ephemerides are compared. Both need to be
+/// sorted by time, as returned by Horizons.
+pub fn compare_ephemerides<F: Float>(
+    first: &[EphemerisVectorItem<F, DefaultUnits>],
+    second: &[EphemerisVectorItem<F, DefaultUnits>],
+) -> EphemerisDifference<F> {
+    let epochs: Vec<_> = first
+        .iter()
+        .filter_map(|a| {
+            let b = &second[second.binary_search_by_key(&a.time, |b| b.time).ok()?];
+            Some(EpochDifference {
+                time: a.time,
+                position: std::array::from_fn(|i| b.position[i] - a.position[i]),
+                velocity: std::array::from_fn(|i| b.velocity[i] - a.velocity[i]),
+            })
+        })
+        .collect();
+
+    EphemerisDifference {
+        max_position_deviation: epochs
+            .iter()
+            .map(EpochDifference::position_deviation)
+            .fold(F::zero(), F::max),
+        max_velocity_deviation: epochs
+            .iter()
+            .map(EpochDifference::velocity_deviation)
+            .fold(F::zero(), F::max),
+        epochs,
+    }
+}
+
+/// Add `new` entries to the ephemeris, keeping it sorted by time. Entries at
+/// epochs already present are dropped.
+pub(crate) fn merge_ephemerides<F: Float>(
+    items: &mut Vec<EphemerisVectorItem<F, DefaultUnits>>,
+    new: Vec<EphemerisVectorItem<F, DefaultUnits>>,
+) {
+    items.extend(new);
+    // Sort is stable, so the entries which were already there come first and
+    // survive the deduplication.
+    items.sort_by_key(|item| item.time);
+    items.dedup_by_key(|item| item.time);
+}
+
+/// Entry of the ephemeris at `time`, interpolated from the entries around it.
+/// `None` if `time` is outside of the ephemeris, which must be sorted by time.
+///
+/// Position is interpolated with cubic Hermite spline, which makes use of the
+/// velocities at both ends, velocity is its derivative and light time is
+/// linear. Uncertainties are dropped, unless `time` is exactly at an entry.
+pub(crate) fn interpolate_vector<F: Float>(
+    items: &[EphemerisVectorItem<F, DefaultUnits>],
+    time: DateTime<Utc>,
+) -> Option<EphemerisVectorItem<F, DefaultUnits>> {
+    let after = items.partition_point(|item| item.time <= time);
+    let a = items.get(after.checked_sub(1)?)?;
+    if a.time == time {
+        return Some(EphemerisVectorItem {
+            time,
+            position: a.position,
+            velocity: a.velocity,
+            light_time: a.light_time,
+            position_uncertainty: a.position_uncertainty,
+            velocity_uncertainty: a.velocity_uncertainty,
+        });
+    }
+    let b = items.get(after)?;
+
+    let h = seconds_between::<F>(a.time, b.time);
+    let s = seconds_between::<F>(a.time, time) / h;
+    let [one, two, three, four, six] = [1, 2, 3, 4, 6].map(|n| F::from(n).unwrap());
+
+    // Hermite basis functions and their derivatives with respect to `s`.
+    let h00 = two * s * s * s - three * s * s + one;
+    let h10 = s * s * s - two * s * s + s;
+    let h01 = -two * s * s * s + three * s * s;
+    let h11 = s * s * s - s * s;
+    let d00 = six * s * s - six * s;
+    let d10 = three * s * s - four * s + one;
+    let d01 = -six * s * s + six * s;
+    let d11 = three * s * s - two * s;
+
+    Some(EphemerisVectorItem {
+        time,
+        position: std::array::from_fn(|i| {
+            h00 * a.position[i]
+                + h10 * h * a.velocity[i]
+                + h01 * b.position[i]
+                + h11 * h * b.velocity[i]
+        }),
+        velocity: std::array::from_fn(|i| {
+            (d00 * a.position[i] + d01 * b.position[i]) / h
+                + d10 * a.velocity[i]
+                + d11 * b.velocity[i]
+        }),
+        light_time: a.light_time + (b.light_time - a.light_time) * s,
+        position_uncertainty: None,
+        velocity_uncertainty: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn item(seconds: i64, velocity: [f32; 3]) -> EphemerisVectorItem<f32, DefaultUnits> {
+        EphemerisVectorItem {
+            time: Utc.with_ymd_and_hms(2022, 8, 13, 0, 0, 0).unwrap() + Duration::seconds(seconds),
+            position: [0.0; 3],
+            velocity,
+            light_time: 0.0,
+            position_uncertainty: None,
+            velocity_uncertainty: None,
+        }
+    }
+
+    fn assert_close(expected: &[[f32; 3]], actual: &[[f32; 3]]) {
+        assert_eq!(expected.len(), actual.len());
+        for (expected, actual) in expected.iter().zip(actual) {
+            for (expected, actual) in expected.iter().zip(actual) {
+                assert!(
+                    (expected - actual).abs() < 1e-5,
+                    "{} != {}",
+                    expected,
+                    actual
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn estimating_constant_acceleration() {
+        // v = 2 t, unevenly sampled.
+        let items = [
+            item(0, [0.0, 0.0, 1.0]),
+            item(10, [20.0, -10.0, 1.0]),
+            item(30, [60.0, -30.0, 1.0]),
+            item(40, [80.0, -40.0, 1.0]),
+        ];
+
+        assert_close(&[[2.0, -1.0, 0.0]; 4], &estimate_accelerations(&items));
+    }
+
+    #[test]
+    fn estimating_varying_acceleration() {
+        // v = t², so a = 2 t. Second order differences are exact for quadratic
+        // velocity, at the ends as well.
+        let items = [item(0, [0.0; 3]), item(1, [1.0; 3]), item(3, [9.0; 3])];
+
+        assert_close(
+            &[[0.0; 3], [2.0; 3], [6.0; 3]],
+            &estimate_accelerations(&items),
+        );
+    }
+
+    #[test]
+    fn estimating_acceleration_from_two_entries() {
+        let items = [item(0, [0.0, 1.0, 2.0]), item(10, [20.0, 1.0, -8.0])];
+
+        assert_close(&[[2.0, 0.0, -1.0]; 2], &estimate_accelerations(&items));
+    }
+
+    #[test]
+    fn estimating_acceleration_requires_increasing_time() {
+        assert!(estimate_accelerations::<f32>(&[]).is_empty());
+        assert!(estimate_accelerations(&[item(0, [0.0; 3])]).is_empty());
+
+        let repeated = [item(0, [0.0; 3]), item(10, [1.0; 3]), item(10, [2.0; 3])];
+        assert!(estimate_accelerations(&repeated).is_empty());
+
+        let reversed = [item(10, [1.0; 3]), item(0, [0.0; 3])];
+        assert!(estimate_accelerations(&reversed).is_empty());
+    }
+
+    #[test]
+    fn comparing_ephemerides() {
+        let at = |seconds, position, velocity| EphemerisVectorItem {
+            position,
+            ..item(seconds, velocity)
+        };
+
+        let first = [
+            at(0, [0.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+            at(60, [60.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+            at(120, [120.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+        ];
+        let second = [
+            at(60, [63.0, 4.0, 0.0], [1.0, 0.0, 0.0]),
+            at(120, [120.0, 0.0, 1.0], [1.0, 2.0, 0.0]),
+            at(180, [180.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+        ];
+
+        let difference = compare_ephemerides(&first, &second);
+
+        assert_eq!(
+            vec![
+                EpochDifference {
+                    time: first[1].time,
+                    position: [3.0, 4.0, 0.0],
+                    velocity: [0.0, 0.0, 0.0],
+                },
+                EpochDifference {
+                    time: first[2].time,
+                    position: [0.0, 0.0, 1.0],
+                    velocity: [0.0, 2.0, 0.0],
+                },
+            ],
+            difference.epochs
+        );
+        assert_eq!(5.0, difference.max_position_deviation);
+        assert_eq!(2.0, difference.max_velocity_deviation);
+    }
+
+    #[test]
+    fn comparing_ephemerides_without_common_epochs() {
+        let difference = compare_ephemerides(&[item(0, [1.0; 3])], &[item(60, [2.0; 3])]);
+
+        assert!(difference.epochs.is_empty());
+        assert_eq!(0.0, difference.max_position_deviation);
+        assert_eq!(0.0, difference.max_velocity_deviation);
+    }
+
+    #[test]
+    fn merging_ephemerides() {
+        let mut items = vec![item(0, [1.0; 3]), item(60, [1.0; 3])];
+        merge_ephemerides(
+            &mut items,
+            vec![item(0, [2.0; 3]), item(30, [2.0; 3]), item(90, [2.0; 3])],
+        );
+
+        assert_eq!(
+            vec![
+                item(0, [1.0; 3]),
+                item(30, [2.0; 3]),
+                item(60, [1.0; 3]),
+                item(90, [2.0; 3]),
+            ],
+            items
+        );
+    }
+
+    #[test]
+    fn interpolating_vectors() {
+        // x = t³, so v = 3 t², which cubic spline follows exactly.
+        let at = |seconds: i64| {
+            let t = seconds as f32;
+            EphemerisVectorItem {
+                position: [t * t * t, 0.0, 1.0],
+                light_time: t,
+                ..item(seconds, [3.0 * t * t, 0.0, 0.0])
+            }
+        };
+        let items = [at(0), at(2), at(6)];
+
+        let interpolated = interpolate_vector(&items, at(3).time).unwrap();
+        assert_close(&[at(3).position], &[interpolated.position]);
+        assert_close(&[at(3).velocity], &[interpolated.velocity]);
+        assert_eq!(3.0, interpolated.light_time);
+
+        assert_eq!(Some(at(6)), interpolate_vector(&items, at(6).time));
+        assert_eq!(None, interpolate_vector(&items, at(-1).time));
+        assert_eq!(None, interpolate_vector(&items, at(7).time));
+        assert_eq!(None, interpolate_vector::<f32>(&[], at(0).time));
+    }
+}
@@ -0,0 +1,86 @@
+/// Version of the key format, bumped whenever the same parameters would give
+/// a different key.
+const VERSION: &str = "v1";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Stable, content-addressed key of a Horizons query, e.g. to name a file the
+/// result is stored in, or to check whether it already is.
+///
+/// The key is `v1-` followed by 16 hex digits of 64-bit FNV-1a hash of the
+/// parameters, with keys and values trimmed and pairs sorted, so the order in
+/// which they are given does not matter. Each key and value is hashed as its
+/// length (8 bytes, little endian) followed by its UTF-8 bytes. The key does
+/// not depend on the platform nor on the version of this crate, unless the
+/// prefix changes.
+///
+/// ```
+/// # use rhorizons::cache_key;
+/// assert_eq!(
+///     cache_key(&[("CENTER", "500@10"), ("COMMAND", "399")]),
+///     cache_key(&[("COMMAND", "399"), ("CENTER", "500@10")])
+/// );
+/// ```
+///
+/// Keys of [`crate::EphemerisQuery`] and [`crate::ProvenanceBundle`] are
+/// computed this way, so a query and its archived result give the same key.
+pub fn cache_key(parameters: &[(impl AsRef<str>, impl AsRef<str>)]) -> String {
+    let mut parameters: Vec<_> = parameters
+        .iter()
+        .map(|(key, value)| (key.as_ref().trim(), value.as_ref().trim()))
+        .collect();
+    parameters.sort_unstable();
+
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut write = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    for (key, value) in parameters {
+        for field in [key, value] {
+            write(&(field.len() as u64).to_le_bytes());
+            write(field.as_bytes());
+        }
+    }
+
+    format!("{}-{:016x}", VERSION, hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_cache_key() {
+        assert_eq!(
+            "v1-eae9f55f48ec134d",
+            cache_key(&[
+                ("COMMAND", "399"),
+                ("CENTER", "500@10"),
+                ("EPHEM_TYPE", "VECTORS"),
+                ("START_TIME", "2016-Oct-15-12:00:00"),
+                ("STOP_TIME", "2016-Oct-15-13:00:00"),
+            ])
+        );
+        assert_eq!("v1-cbf29ce484222325", cache_key(&[] as &[(&str, &str)]));
+    }
+
+    #[test]
+    fn normalizing_parameters() {
+        let key = cache_key(&[("COMMAND", "399"), ("STEP_SIZE", "10 m")]);
+
+        assert_eq!(
+            key,
+            cache_key(&[(" STEP_SIZE", "10 m "), ("COMMAND", "399")])
+        );
+        assert_ne!(key, cache_key(&[("COMMAND", "399"), ("STEP_SIZE", "1 m")]));
+        assert_ne!(key, cache_key(&[("COMMAND", "399")]));
+
+        // Moving characters between the key and the value changes the key.
+        assert_ne!(cache_key(&[("AB", "C")]), cache_key(&[("A", "BC")]));
+    }
+}
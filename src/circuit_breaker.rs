@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+enum State {
+    Closed,
+    Open {
+        until: Instant,
+    },
+    /// Cooldown is over and a single probe request was let through. If it
+    /// doesn't report back by `deadline` (e.g. it was cancelled), another one
+    /// is allowed.
+    HalfOpen {
+        deadline: Instant,
+    },
+}
+
+/// Stops issuing requests for a while after too many consecutive failures.
+///
+/// Once open, the breaker rejects requests for `cooldown`. After that, a single
+/// probe request is let through. If it succeeds, the breaker closes, otherwise
+/// it opens again. Threshold of zero disables the breaker.
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    failures: u32,
+    state: State,
+}
+
+impl CircuitBreaker {
+    pub const fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            failures: 0,
+            state: State::Closed,
+        }
+    }
+
+    pub fn configure(&mut self, threshold: u32, cooldown: Duration) {
+        *self = Self::new(threshold, cooldown);
+    }
+
+    /// Whether a request can be issued now. Must be followed by reporting its
+    /// outcome.
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        match self.state {
+            State::Closed => true,
+            State::Open { until } | State::HalfOpen { deadline: until } if now >= until => {
+                self.state = State::HalfOpen {
+                    deadline: now + self.cooldown,
+                };
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => false,
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.state = State::Closed;
+    }
+
+    pub fn record_failure(&mut self, now: Instant) {
+        self.failures += 1;
+        let open = match self.state {
+            State::Closed => self.threshold > 0 && self.failures >= self.threshold,
+            // Probe failed.
+            State::HalfOpen { .. } => true,
+            State::Open { .. } => false,
+        };
+        if open {
+            self.state = State::Open {
+                until: now + self.cooldown,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn circuit_breaker_opens_after_threshold() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(2, COOLDOWN);
+        assert!(breaker.try_acquire(now));
+
+        breaker.record_failure(now);
+        assert!(breaker.try_acquire(now));
+
+        breaker.record_failure(now);
+        assert!(!breaker.try_acquire(now));
+        assert!(!breaker.try_acquire(now + Duration::from_secs(9)));
+    }
+
+    #[test]
+    fn circuit_breaker_lets_single_probe_through() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(1, COOLDOWN);
+        breaker.record_failure(now);
+
+        let later = now + COOLDOWN;
+        assert!(breaker.try_acquire(later));
+        assert!(!breaker.try_acquire(later));
+
+        // Probe failed.
+        breaker.record_failure(later);
+        assert!(!breaker.try_acquire(later + Duration::from_secs(9)));
+
+        let even_later = later + COOLDOWN;
+        assert!(breaker.try_acquire(even_later));
+        breaker.record_success();
+        assert!(breaker.try_acquire(even_later));
+        assert!(breaker.try_acquire(even_later));
+    }
+
+    #[test]
+    fn circuit_breaker_replaces_lost_probe() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(1, COOLDOWN);
+        breaker.record_failure(now);
+
+        // Probe never reports back.
+        assert!(breaker.try_acquire(now + COOLDOWN));
+        assert!(!breaker.try_acquire(now + COOLDOWN + Duration::from_secs(9)));
+        assert!(breaker.try_acquire(now + COOLDOWN * 2));
+    }
+
+    #[test]
+    fn circuit_breaker_can_be_disabled() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(1, COOLDOWN);
+        breaker.configure(0, COOLDOWN);
+
+        for _ in 0..100 {
+            breaker.record_failure(now);
+        }
+        assert!(breaker.try_acquire(now));
+    }
+}
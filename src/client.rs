@@ -1,132 +1,284 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 use crate::{
+    circuit_breaker::CircuitBreaker,
     ephemeris::{
         EphemerisOrbitalElementsItem, EphemerisOrbitalElementsParser, EphemerisVectorItem,
         EphemerisVectorParser,
     },
-    major_bodies::MajorBody,
+    major_bodies::{parse_search_result, MajorBody},
+    provenance::{HorizonsSignature, ProvenanceBundle},
+    EphemerisQuery, TimeSpec,
 };
 
-/// Generic Horizons response. Their API just gives some JSON with two field,
-/// some statuses and `result` field which is just human-readable string
-/// normally seen in telnet or web API.
-#[derive(Deserialize, Debug)]
+/// Successful Horizons response, see [`RawHorizonsResponse`] for what comes
+/// over the wire. Besides the `signature` of the API, it has the `result`
+/// field which is just human-readable string normally seen in telnet or web
+/// API.
+#[derive(Debug)]
 struct HorizonsResponse {
+    signature: HorizonsSignature,
     result: String,
 }
 
+/// What actually comes over the wire. When Horizons doesn't like the
+/// parameters, there is `error` instead of `result`.
+#[derive(Deserialize, Debug)]
+struct RawHorizonsResponse {
+    signature: Option<HorizonsSignature>,
+    result: Option<String>,
+    error: Option<String>,
+}
+
 #[derive(Error, Debug)]
-#[error("error returned from Horizons")]
-struct HorizonsQueryError;
+pub(crate) enum HorizonsQueryError {
+    /// Horizons could not be reached or failed on its side. Worth retrying.
+    #[error("Horizons is unavailable")]
+    Unavailable,
+    /// Horizons failed too many times recently, so it wasn't even tried.
+    #[error("Horizons keeps failing, circuit breaker is open")]
+    CircuitBreakerOpen,
+    /// Horizons refused the query, e.g. because of invalid parameters.
+    /// Retrying won't help.
+    #[error("Horizons rejected the query: {0}")]
+    Rejected(String),
+}
 
-/// Query the Horizons API, returning a result in form of lines.
-async fn query<T>(parameters: &T) -> Result<Vec<String>, HorizonsQueryError>
+/// Query the Horizons API.
+async fn query<T>(parameters: &T) -> Result<HorizonsResponse, HorizonsQueryError>
 where
     T: Serialize,
 {
-    let result = reqwest::Client::new()
+    let response = reqwest::Client::new()
         .get("https://ssd.jpl.nasa.gov/api/horizons.api")
         .query(parameters)
         .send()
         .await
-        .map_err(|_| HorizonsQueryError)?
-        .json::<HorizonsResponse>()
-        .await
-        .map_err(|_| HorizonsQueryError)?
-        .result
-        .split('\n')
-        .map(str::to_owned)
-        .collect::<Vec<String>>();
+        .map_err(|_| HorizonsQueryError::Unavailable)?;
 
-    for line in &result {
-        log::trace!("{}", line);
+    let status = response.status();
+    if status.is_server_error() {
+        return Err(HorizonsQueryError::Unavailable);
     }
 
-    Ok(result)
+    let response = response.json::<RawHorizonsResponse>().await.map_err(|_| {
+        match status.is_client_error() {
+            true => HorizonsQueryError::Rejected(status.to_string()),
+            false => HorizonsQueryError::Unavailable,
+        }
+    })?;
+
+    match response {
+        RawHorizonsResponse {
+            error: Some(error), ..
+        } => Err(HorizonsQueryError::Rejected(error)),
+        RawHorizonsResponse {
+            signature: Some(signature),
+            result: Some(result),
+            ..
+        } => {
+            for line in result.lines() {
+                log::trace!("{}", line);
+            }
+            Ok(HorizonsResponse { signature, result })
+        }
+        _ => Err(HorizonsQueryError::Unavailable),
+    }
+}
+
+/// Shared by all queries in the process, so that during Horizons outage we
+/// back off instead of every caller retrying on its own.
+static CIRCUIT_BREAKER: Mutex<CircuitBreaker> =
+    Mutex::new(CircuitBreaker::new(5, Duration::from_secs(30)));
+
+/// Configure the circuit breaker shared by all queries in the process.
+///
+/// Each query is retried up to 9 times, a second apart, when Horizons can't be
+/// reached or fails on its side. After `threshold` queries in a row (5 by
+/// default) fail this way, the breaker opens and queries panic immediately
+/// instead of trying, for the `cooldown` (30 seconds by default). Then a single
+/// query is let through to probe whether Horizons is back, others keep
+/// panicking until it succeeds. Queries rejected by Horizons, e.g. because of
+/// invalid parameters, don't count as failures.
+///
+/// Threshold of zero disables the breaker.
+pub fn set_circuit_breaker(threshold: u32, cooldown: Duration) {
+    CIRCUIT_BREAKER
+        .lock()
+        .unwrap()
+        .configure(threshold, cooldown);
 }
 
-async fn query_with_retries<T>(parameters: &T) -> Vec<String>
+async fn try_query_with_retries<T>(parameters: &T) -> Result<HorizonsResponse, HorizonsQueryError>
 where
     T: Serialize,
 {
+    if !CIRCUIT_BREAKER.lock().unwrap().try_acquire(Instant::now()) {
+        return Err(HorizonsQueryError::CircuitBreakerOpen);
+    }
+
     for n in 1..10 {
         log::trace!("try {}", n);
-        if let Ok(result) = query(parameters).await {
-            return result;
+        match query(parameters).await {
+            Ok(result) => {
+                CIRCUIT_BREAKER.lock().unwrap().record_success();
+                return Ok(result);
+            }
+            Err(HorizonsQueryError::Rejected(error)) => {
+                // Horizons did respond, so as far as the breaker is concerned,
+                // it works.
+                CIRCUIT_BREAKER.lock().unwrap().record_success();
+                return Err(HorizonsQueryError::Rejected(error));
+            }
+            Err(_) => (),
         }
-        tokio::time::sleep(std::time::Duration::from_secs(1)).await
+        tokio::time::sleep(Duration::from_secs(1)).await
     }
+
+    // The whole query counts as a single failure, so the breaker opens only
+    // after several queries gave up, not in the middle of the first one.
+    CIRCUIT_BREAKER
+        .lock()
+        .unwrap()
+        .record_failure(Instant::now());
+    Err(HorizonsQueryError::Unavailable)
+}
+
+async fn query_with_retries<T>(parameters: &T) -> HorizonsResponse
+where
+    T: Serialize,
+{
     // TODO: Don't panic.
-    panic!("max retries exceeded");
+    try_query_with_retries(parameters)
+        .await
+        .unwrap_or_else(|error| panic!("{}", error))
 }
 
 /// Get names and identifiers of all major bodies in the Solar System.
+///
+/// # Panics
+///
+/// When Horizons rejects the query, or stays unavailable after up to 9
+/// attempts a second apart. See [`set_circuit_breaker`] for details.
 pub async fn major_bodies() -> Vec<MajorBody> {
     query_with_retries(&[("COMMAND", "MB")])
         .await
-        .iter()
-        .filter_map(|s| MajorBody::try_from(s.as_str()).ok())
+        .result
+        .lines()
+        .filter_map(|s| MajorBody::try_from(s).ok())
         .collect()
 }
 
+/// Find major bodies whose names match `name`, e.g. `"mar"` gives Mars, Mars
+/// Barycenter, spacecraft orbiting Mars and so on. The search is done by
+/// Horizons, so the full list of major bodies is not downloaded.
+///
+/// # Panics
+///
+/// When Horizons rejects the query, or stays unavailable after up to 9
+/// attempts a second apart. See [`set_circuit_breaker`] for details.
+pub async fn search_major_bodies(name: &str) -> Vec<MajorBody> {
+    let response = query_with_retries(&[
+        ("COMMAND", name),
+        // When there is only one match, Horizons immediately generates the
+        // ephemeris, whose header tells us which body it is. Keep it short.
+        ("OBJ_DATA", "NO"),
+        ("EPHEM_TYPE", "VECTORS"),
+        ("STEP_SIZE", "1 d"),
+    ])
+    .await;
+
+    parse_search_result(&response.result)
+}
+
+pub(crate) async fn try_vectors(
+    parameters: Vec<(&'static str, String)>,
+) -> Result<
+    ProvenanceBundle<EphemerisVectorItem<f32, crate::units::DefaultUnits>>,
+    HorizonsQueryError,
+> {
+    let response = try_query_with_retries(&parameters).await?;
+    let fetched_at = Utc::now();
+
+    let items = EphemerisVectorParser::parse(response.result.lines()).collect();
+    Ok(ProvenanceBundle::new(
+        &parameters,
+        response.signature,
+        &response.result,
+        fetched_at,
+        items,
+    ))
+}
+
+pub(crate) async fn vectors(
+    parameters: Vec<(&'static str, String)>,
+) -> ProvenanceBundle<EphemerisVectorItem<f32, crate::units::DefaultUnits>> {
+    // TODO: Don't panic.
+    try_vectors(parameters)
+        .await
+        .unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub(crate) async fn orbital_elements(
+    parameters: Vec<(&'static str, String)>,
+) -> ProvenanceBundle<EphemerisOrbitalElementsItem<f32, crate::units::DefaultUnits>> {
+    let response = query_with_retries(&parameters).await;
+    let fetched_at = Utc::now();
+
+    let items = EphemerisOrbitalElementsParser::parse(response.result.lines()).collect();
+    ProvenanceBundle::new(
+        &parameters,
+        response.signature,
+        &response.result,
+        fetched_at,
+        items,
+    )
+}
+
 /// Get vector ephemeris (position and velocity) of a major body. Coordinates are
 /// relative to the Sun's center.
+///
+/// See [`EphemerisQuery`] for more options.
+///
+/// # Panics
+///
+/// When Horizons rejects the query, or stays unavailable after up to 9
+/// attempts a second apart. See [`set_circuit_breaker`] for details.
 pub async fn ephemeris_vector(
     id: i32,
     start_time: DateTime<Utc>,
     stop_time: DateTime<Utc>,
 ) -> Vec<EphemerisVectorItem<f32, crate::units::DefaultUnits>> {
-    let result = query_with_retries(&[
-        ("COMMAND", id.to_string().as_str()),
-        // Select Sun as a observer. Note that Solar System Barycenter is in a
-        // slightly different place.
-        // https://astronomy.stackexchange.com/questions/44851/
-        ("CENTER", "500@10"),
-        ("EPHEM_TYPE", "VECTORS"),
-        // https://ssd.jpl.nasa.gov/horizons/manual.html#time
-        (
-            "START_TIME",
-            start_time.format("%Y-%b-%d-%T").to_string().as_str(),
-        ),
-        (
-            "STOP_TIME",
-            stop_time.format("%Y-%b-%d-%T").to_string().as_str(),
-        ),
-    ])
-    .await;
-
-    EphemerisVectorParser::parse(result.iter().map(String::as_str)).collect()
+    EphemerisQuery::new(id, TimeSpec::new(start_time, stop_time))
+        .vectors()
+        .await
+        .items
 }
+
 /// Get orbital element ephemeris (e.g. eccentricity, semi-major axis, ...) of a
 /// major body relative to the Sun's center
+///
+/// See [`EphemerisQuery`] for more options.
+///
+/// # Panics
+///
+/// When Horizons rejects the query, or stays unavailable after up to 9
+/// attempts a second apart. See [`set_circuit_breaker`] for details.
 pub async fn ephemeris_orbital_elements(
     id: i32,
     start_time: DateTime<Utc>,
     stop_time: DateTime<Utc>,
 ) -> Vec<EphemerisOrbitalElementsItem<f32, crate::units::DefaultUnits>> {
-    let result = query_with_retries(&[
-        ("COMMAND", id.to_string().as_str()),
-        // Select Sun as a observer. Note that Solar System Barycenter is in a
-        // slightly different place.
-        // https://astronomy.stackexchange.com/questions/44851/
-        ("CENTER", "500@10"),
-        ("EPHEM_TYPE", "ELEMENTS"),
-        // https://ssd.jpl.nasa.gov/horizons/manual.html#time
-        (
-            "START_TIME",
-            start_time.format("%Y-%b-%d-%T").to_string().as_str(),
-        ),
-        (
-            "STOP_TIME",
-            stop_time.format("%Y-%b-%d-%T").to_string().as_str(),
-        ),
-    ])
-    .await;
-
-    EphemerisOrbitalElementsParser::parse(result.iter().map(String::as_str)).collect()
+    EphemerisQuery::new(id, TimeSpec::new(start_time, stop_time))
+        .orbital_elements()
+        .await
+        .items
 }
 
 #[cfg(feature = "si")]
@@ -135,6 +287,11 @@ pub async fn ephemeris_orbital_elements(
 /// Needs the `si` feature to be enabled
 ///
 /// SI-units from the crate *uom*: <https://docs.rs/uom/0.35.0/uom/>
+///
+/// # Panics
+///
+/// When Horizons rejects the query, or stays unavailable after up to 9
+/// attempts a second apart. See [`set_circuit_breaker`] for details.
 pub async fn ephemeris_vector_si(
     id: i32,
     start_time: DateTime<Utc>,
@@ -153,6 +310,11 @@ pub async fn ephemeris_vector_si(
 /// Needs the `si` feature to be enabled
 ///
 /// SI-units from the crate *uom*: <https://docs.rs/uom/0.35.0/uom/>
+///
+/// # Panics
+///
+/// When Horizons rejects the query, or stays unavailable after up to 9
+/// attempts a second apart. See [`set_circuit_breaker`] for details.
 pub async fn ephemeris_orbital_elements_si(
     id: i32,
     start_time: DateTime<Utc>,
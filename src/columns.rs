@@ -0,0 +1,323 @@
+use chrono::{DateTime, Utc};
+use num_traits::Float;
+use serde::{Deserialize, Serialize};
+
+use crate::{units::Units, EphemerisOrbitalElementsItem, EphemerisVectorItem};
+
+/// Vector ephemeris stored by columns (struct-of-arrays) instead of by entries.
+/// Takes less memory for long ephemerides and makes analysis of a single
+/// column, e.g. looking for the closest approach, faster.
+///
+/// All columns have the same length, `n`-th value of each of them comes from
+/// `n`-th entry. See [`EphemerisVectorItem`] for the meaning of the columns.
+///
+/// ```
+/// # use rhorizons::{DefaultUnits, EphemerisVectorColumns, EphemerisVectorItem};
+/// # let items: Vec<EphemerisVectorItem<f32, DefaultUnits>> = Vec::new();
+/// let columns = EphemerisVectorColumns::from(items);
+/// let highest = columns.position[2].iter().cloned().fold(f32::MIN, f32::max);
+/// ```
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "U::Length: serde::Serialize, U::Velocity: serde::Serialize, U::Time: serde::Serialize",
+    deserialize = "U::Length: serde::Deserialize<'de>, U::Velocity: serde::Deserialize<'de>, U::Time: serde::Deserialize<'de>"
+))]
+pub struct EphemerisVectorColumns<F: Float, U: Units<F>> {
+    /// Timestamps of the entries in UTC
+    pub time: Vec<DateTime<Utc>>,
+    /// Positions, [x, y, z]
+    pub position: [Vec<U::Length>; 3],
+    /// Velocities, [v_x, v_y, v_z]
+    pub velocity: [Vec<U::Velocity>; 3],
+    /// One-way down-leg Newtonian light-times
+    pub light_time: Vec<U::Time>,
+    /// 1-sigma uncertainties of the positions, where known
+    pub position_uncertainty: Vec<Option<[U::Length; 3]>>,
+    /// 1-sigma uncertainties of the velocities, where known
+    pub velocity_uncertainty: Vec<Option<[U::Velocity; 3]>>,
+}
+
+impl<F: Float, U: Units<F>> EphemerisVectorColumns<F, U> {
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+}
+
+impl<F: Float, U: Units<F>> From<Vec<EphemerisVectorItem<F, U>>> for EphemerisVectorColumns<F, U> {
+    fn from(items: Vec<EphemerisVectorItem<F, U>>) -> Self {
+        let mut columns = Self {
+            time: Vec::with_capacity(items.len()),
+            position: std::array::from_fn(|_| Vec::with_capacity(items.len())),
+            velocity: std::array::from_fn(|_| Vec::with_capacity(items.len())),
+            light_time: Vec::with_capacity(items.len()),
+            position_uncertainty: Vec::with_capacity(items.len()),
+            velocity_uncertainty: Vec::with_capacity(items.len()),
+        };
+
+        for item in items {
+            columns.time.push(item.time);
+            for (column, value) in columns.position.iter_mut().zip(item.position) {
+                column.push(value);
+            }
+            for (column, value) in columns.velocity.iter_mut().zip(item.velocity) {
+                column.push(value);
+            }
+            columns.light_time.push(item.light_time);
+            columns.position_uncertainty.push(item.position_uncertainty);
+            columns.velocity_uncertainty.push(item.velocity_uncertainty);
+        }
+
+        columns
+    }
+}
+
+/// # Panics
+///
+/// When the columns differ in length.
+impl<F: Float, U: Units<F>> From<EphemerisVectorColumns<F, U>> for Vec<EphemerisVectorItem<F, U>> {
+    fn from(columns: EphemerisVectorColumns<F, U>) -> Self {
+        let mut position = columns.position.map(Vec::into_iter);
+        let mut velocity = columns.velocity.map(Vec::into_iter);
+        let mut light_time = columns.light_time.into_iter();
+        let mut position_uncertainty = columns.position_uncertainty.into_iter();
+        let mut velocity_uncertainty = columns.velocity_uncertainty.into_iter();
+
+        columns
+            .time
+            .into_iter()
+            .map(|time| EphemerisVectorItem {
+                time,
+                position: std::array::from_fn(|i| position[i].next().unwrap()),
+                velocity: std::array::from_fn(|i| velocity[i].next().unwrap()),
+                light_time: light_time.next().unwrap(),
+                position_uncertainty: position_uncertainty.next().unwrap(),
+                velocity_uncertainty: velocity_uncertainty.next().unwrap(),
+            })
+            .collect()
+    }
+}
+
+/// Orbital element ephemeris stored by columns (struct-of-arrays) instead of by
+/// entries, see [`EphemerisVectorColumns`] for why.
+///
+/// All columns have the same length, `n`-th value of each of them comes from
+/// `n`-th entry. See [`EphemerisOrbitalElementsItem`] for the meaning of the
+/// columns.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "U::Length: serde::Serialize, U::Angle: serde::Serialize, U::AngularVelocity: serde::Serialize, U::Time: serde::Serialize",
+    deserialize = "U::Length: serde::Deserialize<'de>, U::Angle: serde::Deserialize<'de>, U::AngularVelocity: serde::Deserialize<'de>, U::Time: serde::Deserialize<'de>"
+))]
+pub struct EphemerisOrbitalElementsColumns<F: Float, U: Units<F>> {
+    /// Timestamps of the entries in UTC
+    pub time: Vec<DateTime<Utc>>,
+    /// Eccentricities
+    pub eccentricity: Vec<f32>,
+    /// Periapsis distances
+    pub periapsis_distance: Vec<U::Length>,
+    /// Inclinations
+    pub inclination: Vec<U::Angle>,
+    /// Longitudes of the ascending node
+    pub longitude_of_ascending_node: Vec<U::Angle>,
+    /// Arguments of perifocus
+    pub argument_of_perifocus: Vec<U::Angle>,
+    /// Times of periapsis
+    pub time_of_periapsis: Vec<U::Time>,
+    /// Mean motions
+    pub mean_motion: Vec<U::AngularVelocity>,
+    /// Mean anomalies
+    pub mean_anomaly: Vec<U::Angle>,
+    /// True anomalies
+    pub true_anomaly: Vec<U::Angle>,
+    /// Semi-major axes
+    pub semi_major_axis: Vec<U::Length>,
+    /// Apoapsis distances
+    pub apoapsis_distance: Vec<U::Length>,
+    /// Sidereal orbit periods
+    pub siderral_orbit_period: Vec<U::Time>,
+}
+
+impl<F: Float, U: Units<F>> EphemerisOrbitalElementsColumns<F, U> {
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+}
+
+impl<F: Float, U: Units<F>> From<Vec<EphemerisOrbitalElementsItem<F, U>>>
+    for EphemerisOrbitalElementsColumns<F, U>
+{
+    fn from(items: Vec<EphemerisOrbitalElementsItem<F, U>>) -> Self {
+        let mut columns = Self {
+            time: Vec::with_capacity(items.len()),
+            eccentricity: Vec::with_capacity(items.len()),
+            periapsis_distance: Vec::with_capacity(items.len()),
+            inclination: Vec::with_capacity(items.len()),
+            longitude_of_ascending_node: Vec::with_capacity(items.len()),
+            argument_of_perifocus: Vec::with_capacity(items.len()),
+            time_of_periapsis: Vec::with_capacity(items.len()),
+            mean_motion: Vec::with_capacity(items.len()),
+            mean_anomaly: Vec::with_capacity(items.len()),
+            true_anomaly: Vec::with_capacity(items.len()),
+            semi_major_axis: Vec::with_capacity(items.len()),
+            apoapsis_distance: Vec::with_capacity(items.len()),
+            siderral_orbit_period: Vec::with_capacity(items.len()),
+        };
+
+        for item in items {
+            columns.time.push(item.time);
+            columns.eccentricity.push(item.eccentricity);
+            columns.periapsis_distance.push(item.periapsis_distance);
+            columns.inclination.push(item.inclination);
+            columns
+                .longitude_of_ascending_node
+                .push(item.longitude_of_ascending_node);
+            columns
+                .argument_of_perifocus
+                .push(item.argument_of_perifocus);
+            columns.time_of_periapsis.push(item.time_of_periapsis);
+            columns.mean_motion.push(item.mean_motion);
+            columns.mean_anomaly.push(item.mean_anomaly);
+            columns.true_anomaly.push(item.true_anomaly);
+            columns.semi_major_axis.push(item.semi_major_axis);
+            columns.apoapsis_distance.push(item.apoapsis_distance);
+            columns
+                .siderral_orbit_period
+                .push(item.siderral_orbit_period);
+        }
+
+        columns
+    }
+}
+
+/// # Panics
+///
+/// When the columns differ in length.
+impl<F: Float, U: Units<F>> From<EphemerisOrbitalElementsColumns<F, U>>
+    for Vec<EphemerisOrbitalElementsItem<F, U>>
+{
+    fn from(columns: EphemerisOrbitalElementsColumns<F, U>) -> Self {
+        let mut eccentricity = columns.eccentricity.into_iter();
+        let mut periapsis_distance = columns.periapsis_distance.into_iter();
+        let mut inclination = columns.inclination.into_iter();
+        let mut longitude_of_ascending_node = columns.longitude_of_ascending_node.into_iter();
+        let mut argument_of_perifocus = columns.argument_of_perifocus.into_iter();
+        let mut time_of_periapsis = columns.time_of_periapsis.into_iter();
+        let mut mean_motion = columns.mean_motion.into_iter();
+        let mut mean_anomaly = columns.mean_anomaly.into_iter();
+        let mut true_anomaly = columns.true_anomaly.into_iter();
+        let mut semi_major_axis = columns.semi_major_axis.into_iter();
+        let mut apoapsis_distance = columns.apoapsis_distance.into_iter();
+        let mut siderral_orbit_period = columns.siderral_orbit_period.into_iter();
+
+        columns
+            .time
+            .into_iter()
+            .map(|time| EphemerisOrbitalElementsItem {
+                time,
+                eccentricity: eccentricity.next().unwrap(),
+                periapsis_distance: periapsis_distance.next().unwrap(),
+                inclination: inclination.next().unwrap(),
+                longitude_of_ascending_node: longitude_of_ascending_node.next().unwrap(),
+                argument_of_perifocus: argument_of_perifocus.next().unwrap(),
+                time_of_periapsis: time_of_periapsis.next().unwrap(),
+                mean_motion: mean_motion.next().unwrap(),
+                mean_anomaly: mean_anomaly.next().unwrap(),
+                true_anomaly: true_anomaly.next().unwrap(),
+                semi_major_axis: semi_major_axis.next().unwrap(),
+                apoapsis_distance: apoapsis_distance.next().unwrap(),
+                siderral_orbit_period: siderral_orbit_period.next().unwrap(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ephemeris::{EphemerisOrbitalElementsParser, EphemerisVectorParser},
+        units::DefaultUnits,
+    };
+
+    fn vectors() -> Vec<EphemerisVectorItem<f32, DefaultUnits>> {
+        EphemerisVectorParser::parse(include_str!("vector.txt").lines()).collect()
+    }
+
+    fn orbital_elements() -> Vec<EphemerisOrbitalElementsItem<f32, DefaultUnits>> {
+        EphemerisOrbitalElementsParser::parse(include_str!("orbital_elements.txt").lines())
+            .collect()
+    }
+
+    #[test]
+    fn storing_vectors_by_columns() {
+        let items = vectors();
+        let columns = EphemerisVectorColumns::from(vectors());
+
+        assert_eq!(items.len(), columns.len());
+        assert!(!columns.is_empty());
+        for (n, item) in items.iter().enumerate() {
+            assert_eq!(item.time, columns.time[n]);
+            assert_eq!(item.position[2], columns.position[2][n]);
+            assert_eq!(item.velocity[0], columns.velocity[0][n]);
+            assert_eq!(item.light_time, columns.light_time[n]);
+        }
+
+        assert_eq!(items, Vec::from(columns));
+    }
+
+    #[test]
+    fn storing_vector_uncertainties_by_columns() {
+        let parse = || {
+            EphemerisVectorParser::parse(include_str!("vector_uncertainties.txt").lines())
+                .collect::<Vec<_>>()
+        };
+        let items = parse();
+        let columns = EphemerisVectorColumns::from(parse());
+
+        assert_eq!(
+            items[1].position_uncertainty,
+            columns.position_uncertainty[1]
+        );
+        assert_eq!(items, Vec::from(columns));
+    }
+
+    #[test]
+    fn storing_orbital_elements_by_columns() {
+        let items = orbital_elements();
+        let columns = EphemerisOrbitalElementsColumns::from(orbital_elements());
+
+        assert_eq!(items.len(), columns.len());
+        assert!(!columns.is_empty());
+        for (n, item) in items.iter().enumerate() {
+            assert_eq!(item.time, columns.time[n]);
+            assert_eq!(item.eccentricity, columns.eccentricity[n]);
+            assert_eq!(item.semi_major_axis, columns.semi_major_axis[n]);
+            assert_eq!(item.siderral_orbit_period, columns.siderral_orbit_period[n]);
+        }
+
+        assert_eq!(items, Vec::from(columns));
+    }
+
+    #[test]
+    fn storing_nothing_by_columns() {
+        let columns = EphemerisVectorColumns::<f32, DefaultUnits>::from(Vec::new());
+
+        assert!(columns.is_empty());
+        assert_eq!(
+            Vec::<EphemerisVectorItem<f32, DefaultUnits>>::new(),
+            Vec::from(columns)
+        );
+    }
+}
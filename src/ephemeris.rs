@@ -1,7 +1,8 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 
-use crate::utilities::{take_expecting, take_or_empty};
+use crate::utilities::{labelled_values, take_expecting, take_or_empty};
 use num_traits::Float;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "si")]
 use uom::si::{angle, angular_velocity, length, time, velocity};
@@ -19,7 +20,13 @@ use uom::si::{angle, angular_velocity, length, time, velocity};
 /// | LT              | One-way down-leg Newtonian light-time           | sec                   |
 /// | RG              | Range; distance from coordinate center          | km                    |
 /// | RR              | Range-rate; radial velocity wrt coord. center   | km/sec                |
-#[derive(Debug, PartialEq)]
+/// | X_s, Y_s, Z_s   | 1-sigma uncertainty of position components      | km                    |
+/// | VX_s, VY_s, VZ_s| 1-sigma uncertainty of velocity components      | km/sec                |
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "U::Length: serde::Serialize, U::Velocity: serde::Serialize, U::Time: serde::Serialize",
+    deserialize = "U::Length: serde::Deserialize<'de>, U::Velocity: serde::Deserialize<'de>, U::Time: serde::Deserialize<'de>"
+))]
 pub struct EphemerisVectorItem<F: Float, U: crate::units::Units<F>> {
     /// Timestamp of the entry in UTC
     pub time: DateTime<Utc>,
@@ -33,6 +40,81 @@ pub struct EphemerisVectorItem<F: Float, U: crate::units::Units<F>> {
     ///
     /// [v_x, v_y, v_z]
     pub velocity: [U::Velocity; 3],
+
+    /// One-way down-leg Newtonian light-time in seconds
+    ///
+    /// Time it takes for light to travel from the body to the center.
+    pub light_time: U::Time,
+
+    /// 1-sigma uncertainty in km of the position
+    ///
+    /// [x, y, z], present only when requested with
+    /// [`crate::EphemerisQuery::with_uncertainties`] and known to Horizons,
+    /// i.e. for small bodies whose orbit solution has a covariance.
+    #[serde(default)]
+    pub position_uncertainty: Option<[U::Length; 3]>,
+
+    /// 1-sigma uncertainty in km/s of the velocity
+    ///
+    /// [v_x, v_y, v_z], present under the same conditions as
+    /// `position_uncertainty`.
+    #[serde(default)]
+    pub velocity_uncertainty: Option<[U::Velocity; 3]>,
+}
+
+impl<F: Float> EphemerisVectorItem<F, crate::units::DefaultUnits> {
+    /// Position in km where the body was when the light seen at the center
+    /// left it.
+    ///
+    /// Geometric position is moved back along the velocity vector by the
+    /// light time, which assumes the velocity is constant during that time.
+    pub fn apparent_position(&self) -> [F; 3] {
+        std::array::from_fn(|i| self.position[i] - self.velocity[i] * self.light_time)
+    }
+
+    /// Magnitude in km of the 1-sigma position uncertainty, if it is known.
+    pub fn position_error(&self) -> Option<F> {
+        self.position_uncertainty
+            .map(|[x, y, z]| (x * x + y * y + z * z).sqrt())
+    }
+}
+
+#[cfg(feature = "si")]
+impl<F> EphemerisVectorItem<F, crate::units::SiUnits>
+where
+    F: Float + uom::Conversion<F, T = F>,
+    uom::si::length::meter: uom::Conversion<F, T = F>,
+    uom::si::mass::kilogram: uom::Conversion<F, T = F>,
+    uom::si::time::second: uom::Conversion<F, T = F>,
+    uom::si::electric_current::ampere: uom::Conversion<F, T = F>,
+    uom::si::thermodynamic_temperature::kelvin: uom::Conversion<F, T = F>,
+    uom::si::amount_of_substance::mole: uom::Conversion<F, T = F>,
+    uom::si::luminous_intensity::candela: uom::Conversion<F, T = F>,
+{
+    /// Position where the body was when the light seen at the center left it.
+    ///
+    /// Geometric position is moved back along the velocity vector by the
+    /// light time, which assumes the velocity is constant during that time.
+    pub fn apparent_position(&self) -> [length::Length<uom::si::SI<F>, F>; 3] {
+        // Values are kept in base units, i.e. m, m/s and s.
+        std::array::from_fn(|i| {
+            length::Length::new::<length::meter>(
+                self.position[i].value - self.velocity[i].value * self.light_time.value,
+            )
+        })
+    }
+
+    /// Magnitude of the 1-sigma position uncertainty, if it is known.
+    pub fn position_error(&self) -> Option<length::Length<uom::si::SI<F>, F>> {
+        self.position_uncertainty.as_ref().map(|uncertainty| {
+            length::Length::new::<length::meter>(
+                uncertainty
+                    .iter()
+                    .fold(F::zero(), |sum, sigma| sum + sigma.value * sigma.value)
+                    .sqrt(),
+            )
+        })
+    }
 }
 
 /// Orbital Elements of a body. Units are km, s and degrees
@@ -53,7 +135,11 @@ pub struct EphemerisVectorItem<F: Float, U: crate::units::Units<F>> {
 /// | PR              | Sidereal orbit period       |                     | sec                 |
 ///
 /// For a detailed explenation of keplarian orbital elements, visit [Wikipedia](https://en.wikipedia.org/wiki/Orbital_elements)
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "U::Length: serde::Serialize, U::Angle: serde::Serialize, U::AngularVelocity: serde::Serialize, U::Time: serde::Serialize",
+    deserialize = "U::Length: serde::Deserialize<'de>, U::Angle: serde::Deserialize<'de>, U::AngularVelocity: serde::Deserialize<'de>, U::Time: serde::Deserialize<'de>"
+))]
 pub struct EphemerisOrbitalElementsItem<F: Float, U: crate::units::Units<F>> {
     /// Timestamp of the entry in UTC
     pub time: DateTime<Utc>,
@@ -150,6 +236,13 @@ where
             time: item.time,
             position: position.try_into().unwrap(),
             velocity: velocity.try_into().unwrap(),
+            light_time: time::Time::new::<time::second>(item.light_time),
+            position_uncertainty: item
+                .position_uncertainty
+                .map(|uncertainty| uncertainty.map(length::Length::new::<length::kilometer>)),
+            velocity_uncertainty: item.velocity_uncertainty.map(|uncertainty| {
+                uncertainty.map(velocity::Velocity::new::<velocity::kilometer_per_second>)
+            }),
         }
     }
 }
@@ -252,7 +345,7 @@ enum EphemerisOrbitalElementsParserState {
 
 pub struct EphemerisVectorParser<'a, Input: Iterator<Item = &'a str>> {
     state: EphemerisVectorParserState,
-    input: Input,
+    input: std::iter::Peekable<Input>,
 }
 
 pub struct EphemerisOrbitalElementsParser<'a, Input: Iterator<Item = &'a str>> {
@@ -264,7 +357,7 @@ impl<'a, Input: Iterator<Item = &'a str>> EphemerisVectorParser<'a, Input> {
     pub fn parse(input: Input) -> Self {
         Self {
             state: EphemerisVectorParserState::WaitingForSoe,
-            input,
+            input: input.peekable(),
         }
     }
 }
@@ -340,17 +433,40 @@ impl<'a, Input: Iterator<Item = &'a str>> Iterator for EphemerisVectorParser<'a,
                             ],
                         };
                     }
-                    // Would parse third line and then return Item => ignores third line and returns directly
+                    // Parses last line and return Item
                     EphemerisVectorParserState::Complete {
                         time,
                         position,
                         velocity,
                     } => {
+                        // TODO: Don't panic.
+                        let line = take_expecting(line, " LT=").unwrap();
+                        let (light_time, _) = take_or_empty(line, 22);
+
+                        // Uncertainties, if requested, come in further lines
+                        // of the entry. Unlike the next date, they start with
+                        // a space.
+                        let mut sigmas = [None; 6];
+                        while let Some(line) = self.input.next_if(|line| line.starts_with(' ')) {
+                            for (label, value) in labelled_values(line) {
+                                if let Some(n) = ["X_s", "Y_s", "Z_s", "VX_s", "VY_s", "VZ_s"]
+                                    .iter()
+                                    .position(|expected| *expected == label)
+                                {
+                                    sigmas[n] = value.parse::<f32>().ok();
+                                }
+                            }
+                        }
+                        let [x_s, y_s, z_s, vx_s, vy_s, vz_s] = sigmas;
+
                         self.state = EphemerisVectorParserState::WaitingForDate;
                         return Some(EphemerisVectorItem {
                             time,
                             position,
                             velocity,
+                            light_time: light_time.trim().parse::<f32>().unwrap(),
+                            position_uncertainty: (|| Some([x_s?, y_s?, z_s?]))(),
+                            velocity_uncertainty: (|| Some([vx_s?, vy_s?, vz_s?]))(),
                         });
                     }
                     EphemerisVectorParserState::End => {
@@ -584,12 +700,62 @@ mod tests {
                     -3.362664133558439E-01,
                     1.344100266143978E-02,
                     -5.030275220358716E-03
-                ]
+                ],
+
+                light_time: 2.124544811851363E-02,
+                position_uncertainty: None,
+                velocity_uncertainty: None,
             },
             ephem[0]
         );
     }
 
+    #[test]
+    fn test_parsing_ephemeris_vector_with_uncertainties() {
+        let data = include_str!("vector_uncertainties.txt");
+        let ephem: Vec<_> = EphemerisVectorParser::parse(data.lines()).collect();
+        assert_eq!(2, ephem.len());
+        assert_eq!(
+            Utc.with_ymd_and_hms(2016, 10, 15, 13, 0, 0).unwrap(),
+            ephem[1].time
+        );
+        assert_eq!(4.857734032120394E+02, ephem[0].light_time);
+        assert_eq!(
+            Some([
+                1.132569542917428E+00,
+                8.015231873457690E-01,
+                2.140613512271309E-01
+            ]),
+            ephem[0].position_uncertainty
+        );
+        assert_eq!(
+            Some([
+                1.780553289474122E-07,
+                2.377620150935163E-07,
+                1.210826108318006E-08
+            ]),
+            ephem[0].velocity_uncertainty
+        );
+
+        let error = ephem[0].position_error().unwrap();
+        assert!((error - 1.403914).abs() < 1e-5, "{}", error);
+    }
+
+    #[test]
+    fn test_apparent_position() {
+        let item = EphemerisVectorItem::<f32, crate::units::DefaultUnits> {
+            time: Utc.with_ymd_and_hms(2022, 8, 13, 19, 55, 56).unwrap(),
+            position: [100.0, 200.0, 300.0],
+            velocity: [1.0, -2.0, 0.0],
+            light_time: 10.0,
+            position_uncertainty: None,
+            velocity_uncertainty: None,
+        };
+
+        assert_eq!([90.0, 220.0, 300.0], item.apparent_position());
+        assert_eq!(None, item.position_error());
+    }
+
     #[test]
     fn test_parsing_ephemeris_orbital_elements() {
         let data = include_str!("orbital_elements.txt");
@@ -643,6 +809,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parsing_date_time_with_calendar_steps() {
+        let lines: [&str; 4] = [
+            "2459913.500000000 = A.D. 2022-Nov-30 00:00:00.0000 TDB ",
+            "2459943.500000000 = A.D. 2022-Dec-30 00:00:00.0000 TDB ",
+            "2459974.500000000 = A.D. 2023-Jan-30 00:00:00.0000 TDB ",
+            "2460003.500000000 = A.D. 2023-Feb-28 00:00:00.0000 TDB ",
+        ];
+
+        let expected: [DateTime<Utc>; 4] = [
+            Utc.with_ymd_and_hms(2022, 11, 30, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2022, 12, 30, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 30, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 2, 28, 0, 0, 0).unwrap(),
+        ];
+
+        for (line, expected) in lines.into_iter().zip(expected) {
+            assert_eq!(expected, parse_date_time(line));
+        }
+    }
+
     #[cfg(feature = "si")]
     #[test]
     fn test_parsing_ephemeris_vector_si() {
@@ -666,12 +853,75 @@ mod tests {
                     Velocity::new::<velocity::kilometer_per_second>(-3.362664133558439E-01),
                     Velocity::new::<velocity::kilometer_per_second>(1.344100266143978E-02),
                     Velocity::new::<velocity::kilometer_per_second>(-5.030275220358716E-03)
-                ]
+                ],
+
+                light_time: Time::new::<time::second>(2.124544811851363E-02),
+                position_uncertainty: None,
+                velocity_uncertainty: None,
             },
             ephem[0]
         );
     }
 
+    #[cfg(feature = "si")]
+    #[test]
+    fn test_parsing_ephemeris_vector_with_uncertainties_si() {
+        let data = include_str!("vector_uncertainties.txt");
+        let ephem: Vec<EphemerisVectorItem<f32, SiUnits>> =
+            EphemerisVectorParser::parse(data.lines())
+                .map(EphemerisVectorItem::from)
+                .collect();
+        assert_eq!(
+            Some([
+                Length::new::<length::kilometer>(1.132569542917428E+00),
+                Length::new::<length::kilometer>(8.015231873457690E-01),
+                Length::new::<length::kilometer>(2.140613512271309E-01)
+            ]),
+            ephem[0].position_uncertainty
+        );
+        assert_eq!(
+            Some([
+                Velocity::new::<velocity::kilometer_per_second>(1.780553289474122E-07),
+                Velocity::new::<velocity::kilometer_per_second>(2.377620150935163E-07),
+                Velocity::new::<velocity::kilometer_per_second>(1.210826108318006E-08)
+            ]),
+            ephem[0].velocity_uncertainty
+        );
+
+        let error = ephem[0].position_error().unwrap().get::<length::meter>();
+        assert!((error - 1403.914).abs() < 1e-2, "{}", error);
+    }
+
+    #[cfg(feature = "si")]
+    #[test]
+    fn test_apparent_position_si() {
+        let item = EphemerisVectorItem::<f32, SiUnits> {
+            time: Utc.with_ymd_and_hms(2022, 8, 13, 19, 55, 56).unwrap(),
+            position: [
+                Length::new::<length::kilometer>(100.0),
+                Length::new::<length::kilometer>(200.0),
+                Length::new::<length::kilometer>(300.0),
+            ],
+            velocity: [
+                Velocity::new::<velocity::kilometer_per_second>(1.0),
+                Velocity::new::<velocity::kilometer_per_second>(-2.0),
+                Velocity::new::<velocity::kilometer_per_second>(0.0),
+            ],
+            light_time: Time::new::<time::second>(10.0),
+            position_uncertainty: None,
+            velocity_uncertainty: None,
+        };
+
+        assert_eq!(
+            [
+                Length::new::<length::kilometer>(90.0),
+                Length::new::<length::kilometer>(220.0),
+                Length::new::<length::kilometer>(300.0),
+            ],
+            item.apparent_position()
+        );
+    }
+
     #[cfg(feature = "si")]
     #[test]
     fn test_parsing_ephemeris_orbital_elements_si() {
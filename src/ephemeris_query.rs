@@ -0,0 +1,512 @@
+use chrono::Duration;
+
+use crate::{
+    analysis::{interpolate_vector, merge_ephemerides},
+    cache_key,
+    client::{orbital_elements, try_vectors, vectors, HorizonsQueryError},
+    units::DefaultUnits,
+    Barycenter, EphemerisOrbitalElementsItem, EphemerisVectorItem, HeliocentricElements,
+    MaybeApproximate, ProvenanceBundle, Site, Step, TimeSpec,
+};
+
+/// Object whose ephemeris is queried.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// Major body given by its Horizons id, see [`crate::MajorBody`]
+    MajorBody(i32),
+    /// Object defined by your own orbit solution
+    UserObject(HeliocentricElements),
+}
+
+impl From<i32> for Target {
+    fn from(id: i32) -> Self {
+        Target::MajorBody(id)
+    }
+}
+
+impl From<Barycenter> for Target {
+    fn from(barycenter: Barycenter) -> Self {
+        Target::MajorBody(barycenter.id())
+    }
+}
+
+impl From<HeliocentricElements> for Target {
+    fn from(elements: HeliocentricElements) -> Self {
+        Target::UserObject(elements)
+    }
+}
+
+impl Target {
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Target::MajorBody(id) => vec![("COMMAND", id.to_string())],
+            Target::UserObject(elements) => elements.parameters(),
+        }
+    }
+}
+
+/// Ephemeris query. Coordinates are relative to the Sun's center, unless a
+/// [`Site`] is given.
+///
+/// Results come as [`ProvenanceBundle`], which besides the parsed items keeps
+/// the parameters and metadata of the query, so they can be archived and
+/// reproduced later.
+///
+/// ```no_run
+/// # use rhorizons::{EphemerisQuery, Step, TimeSpec};
+/// # async fn example() {
+/// let earth = EphemerisQuery::new(399, TimeSpec::last_day())
+///     .with_step(Step::Minutes(10.try_into().unwrap()))
+///     .vectors()
+///     .await;
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EphemerisQuery {
+    /// Object whose ephemeris is queried
+    pub target: Target,
+    /// Time range of the ephemeris
+    pub time: TimeSpec,
+    /// Interval between entries, Horizons' default (60 minutes) if `None`
+    pub step: Option<Step>,
+    /// Place on Earth the target is observed from, the Sun's center if `None`
+    pub site: Option<Site>,
+    /// Whether to ask for 1-sigma uncertainties of vectors
+    pub uncertainties: bool,
+}
+
+impl EphemerisQuery {
+    /// Query with Horizons' default step, relative to the Sun's center.
+    pub fn new(target: impl Into<Target>, time: TimeSpec) -> Self {
+        Self {
+            target: target.into(),
+            time,
+            step: None,
+            site: None,
+            uncertainties: false,
+        }
+    }
+
+    /// Space entries by `step`. See [`Step::suggest`] for picking a step which
+    /// fits the time range.
+    pub fn with_step(self, step: Step) -> Self {
+        Self {
+            step: Some(step),
+            ..self
+        }
+    }
+
+    /// Observe the target from `site`, e.g. one picked from a
+    /// [`crate::SiteRegistry`].
+    pub fn with_site(self, site: Site) -> Self {
+        Self {
+            site: Some(site),
+            ..self
+        }
+    }
+
+    /// Ask for 1-sigma uncertainties of position and velocity, see
+    /// [`EphemerisVectorItem::position_uncertainty`]. Horizons knows them only
+    /// for small bodies whose orbit solution has a covariance, e.g. asteroids.
+    pub fn with_uncertainties(self) -> Self {
+        Self {
+            uncertainties: true,
+            ..self
+        }
+    }
+
+    /// All parameters sent to Horizons.
+    pub(crate) fn parameters(&self, ephem_type: &str) -> Vec<(&'static str, String)> {
+        let mut parameters = self.target.parameters();
+        match &self.site {
+            Some(site) => parameters.extend(site.parameters()),
+            // Select Sun as a observer. Note that Solar System Barycenter is in
+            // a slightly different place.
+            // https://astronomy.stackexchange.com/questions/44851/
+            None => parameters.push(("CENTER", "500@10".to_string())),
+        }
+        parameters.extend([
+            ("EPHEM_TYPE", ephem_type.to_string()),
+            // https://ssd.jpl.nasa.gov/horizons/manual.html#time
+            (
+                "START_TIME",
+                self.time.start.format("%Y-%b-%d-%T").to_string(),
+            ),
+            (
+                "STOP_TIME",
+                self.time.stop.format("%Y-%b-%d-%T").to_string(),
+            ),
+        ]);
+        if let Some(step) = self.step {
+            parameters.push(("STEP_SIZE", step.to_string()));
+        }
+        if self.uncertainties && ephem_type == "VECTORS" {
+            // Default table (position, velocity, LT, range and range-rate)
+            // with uncertainties.
+            parameters.push(("VEC_TABLE", "3x".to_string()));
+        }
+        parameters
+    }
+
+    /// Get vector ephemeris (position and velocity).
+    ///
+    /// # Panics
+    ///
+    /// When Horizons rejects the query, or stays unavailable after up to 9
+    /// attempts a second apart. See [`crate::set_circuit_breaker`] for details.
+    pub async fn vectors(&self) -> ProvenanceBundle<EphemerisVectorItem<f32, DefaultUnits>> {
+        vectors(self.parameters("VECTORS")).await
+    }
+
+    /// Get orbital element ephemeris (e.g. eccentricity, semi-major axis, ...).
+    ///
+    /// # Panics
+    ///
+    /// When Horizons rejects the query, or stays unavailable after up to 9
+    /// attempts a second apart. See [`crate::set_circuit_breaker`] for details.
+    pub async fn orbital_elements(
+        &self,
+    ) -> ProvenanceBundle<EphemerisOrbitalElementsItem<f32, DefaultUnits>> {
+        orbital_elements(self.parameters("ELEMENTS")).await
+    }
+
+    /// Key of the [`Self::vectors`] query, see [`cache_key`]. Same as of the
+    /// [`ProvenanceBundle`] it returns.
+    pub fn vectors_cache_key(&self) -> String {
+        cache_key(&self.parameters("VECTORS"))
+    }
+
+    /// Key of the [`Self::orbital_elements`] query, see [`cache_key`]. Same as
+    /// of the [`ProvenanceBundle`] it returns.
+    pub fn orbital_elements_cache_key(&self) -> String {
+        cache_key(&self.parameters("ELEMENTS"))
+    }
+
+    #[cfg(feature = "si")]
+    /// Get vector ephemeris (position and velocity) in SI-units.
+    /// Needs the `si` feature to be enabled
+    ///
+    /// # Panics
+    ///
+    /// When Horizons rejects the query, or stays unavailable after up to 9
+    /// attempts a second apart. See [`crate::set_circuit_breaker`] for details.
+    pub async fn vectors_si(
+        &self,
+    ) -> ProvenanceBundle<EphemerisVectorItem<f32, crate::units::SiUnits>> {
+        self.vectors().await.map(EphemerisVectorItem::from)
+    }
+
+    #[cfg(feature = "si")]
+    /// Get orbital element ephemeris in SI-units.
+    /// Needs the `si` feature to be enabled
+    ///
+    /// # Panics
+    ///
+    /// When Horizons rejects the query, or stays unavailable after up to 9
+    /// attempts a second apart. See [`crate::set_circuit_breaker`] for details.
+    pub async fn orbital_elements_si(
+        &self,
+    ) -> ProvenanceBundle<EphemerisOrbitalElementsItem<f32, crate::units::SiUnits>> {
+        self.orbital_elements()
+            .await
+            .map(EphemerisOrbitalElementsItem::from)
+    }
+
+    /// Get vector ephemeris progressively. First it is fetched with the first
+    /// of `steps`, which should be coarse so it arrives quickly, then refined
+    /// with each next, finer step, ignoring the `step` of the query. After
+    /// every refinement `on_update` is called with all entries gathered so
+    /// far, merged and sorted by time, so e.g. an orrery can draw something
+    /// immediately and improve it as more data arrives. Returns the final
+    /// ephemeris.
+    ///
+    /// To keep refining in the background, spawn it as a task.
+    ///
+    /// ```no_run
+    /// # use rhorizons::{EphemerisQuery, Step, TimeSpec};
+    /// # async fn example() {
+    /// let query = EphemerisQuery::new(399, TimeSpec::next_days(30));
+    /// let task = tokio::spawn(query.vectors_progressive(
+    ///     [Step::Days(1.try_into().unwrap()), Step::Hours(1.try_into().unwrap())],
+    ///     |items| println!("got {} entries so far", items.len()),
+    /// ));
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// When Horizons rejects the query, or stays unavailable after up to 9
+    /// attempts a second apart. See [`crate::set_circuit_breaker`] for details.
+    pub async fn vectors_progressive(
+        self,
+        steps: impl IntoIterator<Item = Step>,
+        mut on_update: impl FnMut(&[EphemerisVectorItem<f32, DefaultUnits>]),
+    ) -> Vec<EphemerisVectorItem<f32, DefaultUnits>> {
+        let mut items = Vec::new();
+        for step in steps {
+            let refined = self.clone().with_step(step).vectors().await;
+            merge_ephemerides(&mut items, refined.items);
+            on_update(&items);
+        }
+        items
+    }
+
+    /// Get vector ephemeris, or when Horizons is unavailable or the circuit
+    /// breaker is open, interpolate it from `coarse` entries, e.g. fetched
+    /// earlier by the same query with a larger step or by
+    /// [`Self::vectors_progressive`]. Meant for applications which rather show
+    /// something slightly off than nothing, e.g. visualizations.
+    ///
+    /// Interpolated entries are at the same epochs Horizons would give, but
+    /// only within the time covered by `coarse`, which needs to be sorted by
+    /// time. With calendar steps, which have no fixed length, `coarse` entries
+    /// within the time range are returned as they are. Unlike [`Self::vectors`],
+    /// this doesn't wait for retries when the breaker is open.
+    ///
+    /// # Panics
+    ///
+    /// When Horizons rejects the query.
+    pub async fn vectors_or_interpolated(
+        &self,
+        coarse: &[EphemerisVectorItem<f32, DefaultUnits>],
+    ) -> MaybeApproximate<EphemerisVectorItem<f32, DefaultUnits>> {
+        match try_vectors(self.parameters("VECTORS")).await {
+            Ok(bundle) => MaybeApproximate::Exact(bundle),
+            // TODO: Don't panic.
+            Err(HorizonsQueryError::Rejected(error)) => {
+                panic!("Horizons rejected the query: {}", error)
+            }
+            Err(error) => {
+                log::warn!("{}, interpolating from coarse ephemeris", error);
+                MaybeApproximate::Approximate(self.interpolated(coarse))
+            }
+        }
+    }
+
+    fn interpolated(
+        &self,
+        coarse: &[EphemerisVectorItem<f32, DefaultUnits>],
+    ) -> Vec<EphemerisVectorItem<f32, DefaultUnits>> {
+        let (Some(first), Some(last)) = (coarse.first(), coarse.last()) else {
+            return Vec::new();
+        };
+        let start = self.time.start.max(first.time);
+        let stop = self.time.stop.min(last.time);
+
+        // Horizons' default step is 60 minutes.
+        let step = match self
+            .step
+            .map_or(Some(Duration::hours(1)), |step| step.duration())
+        {
+            Some(step) => step.num_milliseconds(),
+            None => {
+                return coarse
+                    .iter()
+                    .filter(|item| (start..=stop).contains(&item.time))
+                    .filter_map(|item| interpolate_vector(coarse, item.time))
+                    .collect()
+            }
+        };
+
+        // Epochs are whole steps from the start of the time range, skip those
+        // before `coarse` begins.
+        let skipped = ((start - self.time.start).num_milliseconds() + step - 1) / step;
+        std::iter::successors(
+            self.time
+                .start
+                .checked_add_signed(Duration::milliseconds(skipped * step)),
+            |time| time.checked_add_signed(Duration::milliseconds(step)),
+        )
+        .take_while(|time| *time <= stop)
+        .filter_map(|time| interpolate_vector(coarse, time))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn ephemeris_query_parameters() {
+        let query = EphemerisQuery::new(
+            399,
+            TimeSpec::new(
+                Utc.with_ymd_and_hms(2016, 10, 15, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2016, 10, 15, 13, 0, 0).unwrap(),
+            ),
+        );
+
+        assert_eq!(
+            vec![
+                ("COMMAND", "399".to_string()),
+                ("CENTER", "500@10".to_string()),
+                ("EPHEM_TYPE", "VECTORS".to_string()),
+                ("START_TIME", "2016-Oct-15-12:00:00".to_string()),
+                ("STOP_TIME", "2016-Oct-15-13:00:00".to_string()),
+            ],
+            query.parameters("VECTORS")
+        );
+
+        let parameters = query
+            .with_step(Step::Minutes(10.try_into().unwrap()))
+            .parameters("ELEMENTS");
+        assert_eq!(("EPHEM_TYPE", "ELEMENTS".to_string()), parameters[2]);
+        assert_eq!(Some(&("STEP_SIZE", "10 m".to_string())), parameters.last());
+    }
+
+    #[test]
+    fn ephemeris_query_uncertainties() {
+        let query = EphemerisQuery::new(99942, TimeSpec::last_day()).with_uncertainties();
+
+        assert_eq!(
+            Some(&("VEC_TABLE", "3x".to_string())),
+            query.parameters("VECTORS").last()
+        );
+        assert!(!query
+            .parameters("ELEMENTS")
+            .iter()
+            .any(|(key, _)| *key == "VEC_TABLE"));
+    }
+
+    #[test]
+    fn ephemeris_query_geodetic_site() {
+        let query = EphemerisQuery::new(
+            399,
+            TimeSpec::new(
+                Utc.with_ymd_and_hms(2016, 10, 15, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2016, 10, 15, 13, 0, 0).unwrap(),
+            ),
+        )
+        .with_site(Site::Geodetic {
+            longitude: -116.86,
+            latitude: 33.36,
+            altitude: 1.7,
+        });
+
+        assert_eq!(
+            vec![
+                ("COMMAND", "399".to_string()),
+                ("CENTER", "coord@399".to_string()),
+                ("COORD_TYPE", "GEODETIC".to_string()),
+                ("SITE_COORD", "-116.86,33.36,1.7".to_string()),
+                ("EPHEM_TYPE", "VECTORS".to_string()),
+                ("START_TIME", "2016-Oct-15-12:00:00".to_string()),
+                ("STOP_TIME", "2016-Oct-15-13:00:00".to_string()),
+            ],
+            query.parameters("VECTORS")
+        );
+    }
+
+    #[test]
+    fn ephemeris_query_mpc_site() {
+        let query = EphemerisQuery::new(
+            399,
+            TimeSpec::new(
+                Utc.with_ymd_and_hms(2016, 10, 15, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2016, 10, 15, 13, 0, 0).unwrap(),
+            ),
+        )
+        .with_site(Site::MpcCode("675".to_string()));
+
+        assert_eq!(
+            vec![
+                ("COMMAND", "399".to_string()),
+                ("CENTER", "675@399".to_string()),
+                ("EPHEM_TYPE", "VECTORS".to_string()),
+                ("START_TIME", "2016-Oct-15-12:00:00".to_string()),
+                ("STOP_TIME", "2016-Oct-15-13:00:00".to_string()),
+            ],
+            query.parameters("VECTORS")
+        );
+    }
+
+    #[test]
+    fn interpolating_ephemeris_query() {
+        let at = |hour: u32, x: f32| EphemerisVectorItem {
+            time: Utc.with_ymd_and_hms(2016, 10, 15, hour, 0, 0).unwrap(),
+            position: [x, 0.0, 0.0],
+            velocity: [1.0 / 3600.0, 0.0, 0.0],
+            light_time: 0.0,
+            position_uncertainty: None,
+            velocity_uncertainty: None,
+        };
+        let coarse = [at(11, 0.0), at(12, 1.0), at(13, 2.0)];
+
+        let query = EphemerisQuery::new(
+            399,
+            TimeSpec::new(
+                Utc.with_ymd_and_hms(2016, 10, 15, 10, 20, 0).unwrap(),
+                Utc.with_ymd_and_hms(2016, 10, 15, 14, 0, 0).unwrap(),
+            ),
+        )
+        .with_step(Step::Minutes(20.try_into().unwrap()));
+
+        let interpolated = query.interpolated(&coarse);
+        assert_eq!(7, interpolated.len());
+        assert_eq!(at(11, 0.0), interpolated[0]);
+        assert_eq!(
+            Utc.with_ymd_and_hms(2016, 10, 15, 11, 20, 0).unwrap(),
+            interpolated[1].time
+        );
+        assert!((interpolated[1].position[0] - 1.0 / 3.0).abs() < 1e-5);
+        assert_eq!(at(12, 1.0), interpolated[3]);
+        assert_eq!(at(13, 2.0), interpolated[6]);
+
+        let query = query.with_step(Step::Months(1.try_into().unwrap()));
+        assert_eq!(
+            coarse.iter().map(|item| item.time).collect::<Vec<_>>(),
+            query
+                .interpolated(&coarse)
+                .iter()
+                .map(|item| item.time)
+                .collect::<Vec<_>>()
+        );
+
+        assert!(query.interpolated(&[]).is_empty());
+    }
+
+    #[test]
+    fn ephemeris_query_targets() {
+        assert_eq!(Target::MajorBody(5), Target::from(Barycenter::Jupiter));
+
+        let elements = HeliocentricElements {
+            epoch: 2449600.5,
+            eccentricity: 0.6,
+            perihelion_distance: 1.2,
+            time_of_perihelion: 2449700.5,
+            longitude_of_ascending_node: 90.0,
+            argument_of_perihelion: 30.0,
+            inclination: 15.0,
+        };
+        let query = EphemerisQuery::new(elements, TimeSpec::last_day());
+        assert_eq!(("COMMAND", ";".to_string()), query.parameters("VECTORS")[0]);
+    }
+
+    #[test]
+    fn ephemeris_query_cache_keys() {
+        let query = EphemerisQuery::new(
+            399,
+            TimeSpec::new(
+                Utc.with_ymd_and_hms(2016, 10, 15, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2016, 10, 15, 13, 0, 0).unwrap(),
+            ),
+        );
+
+        assert_eq!(
+            cache_key(&query.parameters("VECTORS")),
+            query.vectors_cache_key()
+        );
+        assert_ne!(
+            query.vectors_cache_key(),
+            query.orbital_elements_cache_key()
+        );
+        assert_ne!(
+            query.vectors_cache_key(),
+            query
+                .with_step(Step::Hours(1.try_into().unwrap()))
+                .vectors_cache_key()
+        );
+    }
+}
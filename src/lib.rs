@@ -1,20 +1,44 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+mod analysis;
+mod cache_key;
+mod circuit_breaker;
 mod client;
+mod columns;
 mod ephemeris;
+mod ephemeris_query;
 mod major_bodies;
+mod provenance;
+mod site;
+mod step;
+mod time_spec;
 mod units;
+mod user_object;
 mod utilities;
 
+pub use analysis::{
+    compare_ephemerides, estimate_accelerations, EphemerisDifference, EpochDifference,
+};
+pub use cache_key::cache_key;
 pub use units::DefaultUnits;
 #[cfg(feature = "si")]
 pub use units::SiUnits;
 
-pub use client::{ephemeris_orbital_elements, ephemeris_vector, major_bodies};
+pub use client::{
+    ephemeris_orbital_elements, ephemeris_vector, major_bodies, search_major_bodies,
+    set_circuit_breaker,
+};
 
 #[cfg(feature = "si")]
 pub use client::{ephemeris_orbital_elements_si, ephemeris_vector_si};
 
+pub use columns::{EphemerisOrbitalElementsColumns, EphemerisVectorColumns};
 pub use ephemeris::{EphemerisOrbitalElementsItem, EphemerisVectorItem};
-pub use major_bodies::MajorBody;
+pub use ephemeris_query::{EphemerisQuery, Target};
+pub use major_bodies::{Barycenter, MajorBody};
+pub use provenance::{HorizonsSignature, MaybeApproximate, ProvenanceBundle};
+pub use site::{Site, SiteRegistry};
+pub use step::Step;
+pub use time_spec::TimeSpec;
+pub use user_object::HeliocentricElements;
@@ -23,6 +23,84 @@ pub struct MajorBody {
     pub name: String,
 }
 
+impl MajorBody {
+    /// Whether this is one of the barycenters rather than an actual body.
+    pub fn is_barycenter(&self) -> bool {
+        self.barycenter().is_some()
+    }
+
+    /// Barycenter which this major body is, if any.
+    pub fn barycenter(&self) -> Option<Barycenter> {
+        Barycenter::from_id(self.id)
+    }
+}
+
+/// Center of mass of the whole Solar System or of a planetary system (a planet
+/// with its moons). Simulations often want these rather than the planets
+/// themselves, especially for the outer planets.
+///
+/// Horizons identifies them with ids from 0 to 9, which can be used as targets
+/// like any other major body.
+///
+/// Example
+/// ```
+/// # use rhorizons::Barycenter;
+/// assert_eq!(5, Barycenter::Jupiter.id());
+/// assert_eq!(Some(Barycenter::EarthMoon), Barycenter::from_id(3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Barycenter {
+    /// Solar System Barycenter (SSB)
+    SolarSystem = 0,
+    /// Mercury Barycenter
+    Mercury = 1,
+    /// Venus Barycenter
+    Venus = 2,
+    /// Earth-Moon Barycenter (EMB)
+    EarthMoon = 3,
+    /// Mars Barycenter
+    Mars = 4,
+    /// Jupiter Barycenter
+    Jupiter = 5,
+    /// Saturn Barycenter
+    Saturn = 6,
+    /// Uranus Barycenter
+    Uranus = 7,
+    /// Neptune Barycenter
+    Neptune = 8,
+    /// Pluto Barycenter
+    Pluto = 9,
+}
+
+impl Barycenter {
+    /// All barycenters known to Horizons, ordered by id.
+    pub const ALL: [Barycenter; 10] = [
+        Barycenter::SolarSystem,
+        Barycenter::Mercury,
+        Barycenter::Venus,
+        Barycenter::EarthMoon,
+        Barycenter::Mars,
+        Barycenter::Jupiter,
+        Barycenter::Saturn,
+        Barycenter::Uranus,
+        Barycenter::Neptune,
+        Barycenter::Pluto,
+    ];
+
+    /// Horizons id of the barycenter.
+    pub fn id(self) -> i32 {
+        self as i32
+    }
+
+    /// Barycenter having given Horizons id, `None` if the id belongs to
+    /// something else.
+    pub fn from_id(id: i32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|barycenter| barycenter.id() == id)
+    }
+}
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum MajorBodyParseError {
     #[error("invalid id")]
@@ -47,6 +125,42 @@ impl TryFrom<&str> for MajorBody {
     }
 }
 
+/// Read the result of a name search. Horizons lists all candidates as a table
+/// in the same format as the full list, unless there is just one match, in
+/// which case it goes straight to the ephemeris of that body.
+///
+/// When no major body matches, Horizons falls back to searching small-bodies
+/// and lists them by record numbers, which are not major body ids, so such
+/// results are treated as no matches at all.
+pub(crate) fn parse_search_result(result: &str) -> Vec<MajorBody> {
+    if result.contains("No matches found") || result.contains("Matching small-bodies") {
+        return Vec::new();
+    }
+
+    if let Some(target) = result
+        .lines()
+        .find_map(|line| line.strip_prefix("Target body name:"))
+    {
+        // E.g. `Mars (499)                      {source: mar097}`.
+        let target = target.split('{').next().unwrap_or_default().trim();
+        return target
+            .rsplit_once(" (")
+            .and_then(|(name, id)| {
+                Some(MajorBody {
+                    id: id.strip_suffix(')')?.parse().ok()?,
+                    name: name.trim().to_string(),
+                })
+            })
+            .into_iter()
+            .collect();
+    }
+
+    result
+        .lines()
+        .filter_map(|s| MajorBody::try_from(s).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,6 +197,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn classifying_barycenters() {
+        for (id, barycenter) in Barycenter::ALL.into_iter().enumerate() {
+            assert_eq!(id as i32, barycenter.id());
+            assert_eq!(Some(barycenter), Barycenter::from_id(id as i32));
+        }
+
+        let ssb =
+            MajorBody::try_from("        0  Solar System Barycenter                         SSB")
+                .unwrap();
+        assert!(ssb.is_barycenter());
+        assert_eq!(Some(Barycenter::SolarSystem), ssb.barycenter());
+
+        let sun =
+            MajorBody::try_from("       10  Sun                                             Sun")
+                .unwrap();
+        assert!(!sun.is_barycenter());
+
+        let saturn = MajorBody::try_from("      699  Saturn").unwrap();
+        assert!(!saturn.is_barycenter());
+
+        assert_eq!(None, Barycenter::from_id(-1));
+    }
+
+    #[test]
+    fn reading_search_result_with_multiple_matches() {
+        let result = r#"*******************************************************************************
+ Multiple major-bodies match string "MAR*"
+
+  ID#      Name                               Designation  IAU/aliases/other
+  -------  ---------------------------------- -----------  -------------------
+        4  Mars Barycenter
+      499  Mars
+      -74  Mars Reconnaissance Orbiter (spacec MRO          2005-029A
+
+   Number of matches =   3. Use ID# to make unique selection.
+*******************************************************************************"#;
+
+        assert_eq!(
+            vec![
+                MajorBody {
+                    id: 4,
+                    name: "Mars Barycenter".to_string()
+                },
+                MajorBody {
+                    id: 499,
+                    name: "Mars".to_string()
+                },
+                MajorBody {
+                    id: -74,
+                    name: "Mars Reconnaissance Orbiter (spac".to_string()
+                },
+            ],
+            parse_search_result(result)
+        );
+    }
+
+    #[test]
+    fn reading_search_result_with_single_match() {
+        let result = "Target body name: Phobos (401)                    {source: mar097}\n\
+                      Center body name: Sun (10)                        {source: DE441}";
+
+        assert_eq!(
+            vec![MajorBody {
+                id: 401,
+                name: "Phobos".to_string()
+            }],
+            parse_search_result(result)
+        );
+
+        let result = "Target body name: 1 Ceres (A801 AA)               {source: JPL#48}";
+        assert_eq!(Vec::<MajorBody>::new(), parse_search_result(result));
+    }
+
+    #[test]
+    fn reading_search_result_with_small_bodies() {
+        let result = r#"*******************************************************************************
+JPL/DASTCOM            Small-body Index Search Results     2023-Jan-01 00:00:00
+
+ Comet AND asteroid index search:
+
+    NAME = APL;
+
+ Matching small-bodies:
+
+    Record #  Epoch-yr  >MATCH NAME<   Primary Desig  Name
+    --------  --------  -------------  -------------  -------------------------
+      132524            APL            2002 JU9       APL
+
+ (1 match. To SELECT, enter record # (integer), followed by semi-colon.)
+*******************************************************************************"#;
+
+        assert_eq!(Vec::<MajorBody>::new(), parse_search_result(result));
+    }
+
+    #[test]
+    fn reading_search_result_without_matches() {
+        let result = r#"*******************************************************************************
+JPL/DASTCOM            Small-body Index Search Results     2023-Jan-01 00:00:00
+
+ Comet AND asteroid index search:
+
+    NAME = XYZZY;
+
+ Matching small-bodies:
+
+    No matches found.
+*******************************************************************************"#;
+
+        assert_eq!(Vec::<MajorBody>::new(), parse_search_result(result));
+    }
+
     #[test]
     fn error_cases_when_parsing_major_bodies() {
         assert!(matches!(
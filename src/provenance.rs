@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cache_key;
+
+/// Signature of the Horizons API which produced the response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HorizonsSignature {
+    /// Name of the service, e.g. "NASA/JPL Horizons API"
+    pub source: String,
+    /// Version of the API
+    pub version: String,
+}
+
+/// Parsed items together with everything needed to tell how they were
+/// obtained. Meant to be archived next to the data, e.g. as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProvenanceBundle<T> {
+    /// Exact parameters sent to Horizons
+    pub parameters: Vec<(String, String)>,
+    /// Signature of the API which answered the query
+    pub signature: HorizonsSignature,
+    /// Ephemeris sources (e.g. DE441) reported by Horizons for the target and
+    /// the center
+    pub source_kernels: Vec<String>,
+    /// When the response was received
+    pub fetched_at: DateTime<Utc>,
+    /// Parsed ephemeris
+    pub items: Vec<T>,
+}
+
+impl<T> ProvenanceBundle<T> {
+    pub(crate) fn new(
+        parameters: &[(&str, String)],
+        signature: HorizonsSignature,
+        result: &str,
+        fetched_at: DateTime<Utc>,
+        items: Vec<T>,
+    ) -> Self {
+        Self {
+            parameters: parameters
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+            signature,
+            source_kernels: source_kernels(result),
+            fetched_at,
+            items,
+        }
+    }
+
+    /// Convert the items, keeping the provenance, e.g. to SI-units.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> ProvenanceBundle<U> {
+        ProvenanceBundle {
+            parameters: self.parameters,
+            signature: self.signature,
+            source_kernels: self.source_kernels,
+            fetched_at: self.fetched_at,
+            items: self.items.into_iter().map(f).collect(),
+        }
+    }
+
+    /// Key of the query which produced the bundle, see [`cache_key`].
+    pub fn cache_key(&self) -> String {
+        cache_key(&self.parameters)
+    }
+}
+
+/// Items fetched from Horizons or, when it was unavailable, approximated from
+/// data at hand. See [`crate::EphemerisQuery::vectors_or_interpolated`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MaybeApproximate<T> {
+    /// Fetched from Horizons
+    Exact(ProvenanceBundle<T>),
+    /// Interpolated, because Horizons was unavailable
+    Approximate(Vec<T>),
+}
+
+impl<T> MaybeApproximate<T> {
+    /// Whether the items are interpolated instead of fetched from Horizons.
+    pub fn is_approximate(&self) -> bool {
+        matches!(self, MaybeApproximate::Approximate(_))
+    }
+
+    /// Items, regardless of where they came from.
+    pub fn items(&self) -> &[T] {
+        match self {
+            MaybeApproximate::Exact(bundle) => &bundle.items,
+            MaybeApproximate::Approximate(items) => items,
+        }
+    }
+
+    /// Items, regardless of where they came from.
+    pub fn into_items(self) -> Vec<T> {
+        match self {
+            MaybeApproximate::Exact(bundle) => bundle.items,
+            MaybeApproximate::Approximate(items) => items,
+        }
+    }
+}
+
+/// Collect unique `{source: ...}` annotations from the response header, e.g.
+/// `Target body name: Earth (399)  {source: DE441}`.
+fn source_kernels(result: &str) -> Vec<String> {
+    let mut kernels = Vec::new();
+    for line in result.lines() {
+        if let Some((_, rest)) = line.split_once("{source: ") {
+            if let Some((kernel, _)) = rest.split_once('}') {
+                let kernel = kernel.trim().to_string();
+                if !kernels.contains(&kernel) {
+                    kernels.push(kernel);
+                }
+            }
+        }
+    }
+    kernels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ephemeris::EphemerisVectorParser, units::DefaultUnits, EphemerisVectorItem};
+    use chrono::TimeZone;
+
+    fn vector_bundle() -> ProvenanceBundle<EphemerisVectorItem<f32, DefaultUnits>> {
+        let data = include_str!("vector.txt");
+        ProvenanceBundle::new(
+            &[("COMMAND", "399".to_string())],
+            HorizonsSignature {
+                source: "NASA/JPL Horizons API".to_string(),
+                version: "1.1".to_string(),
+            },
+            data,
+            Utc.with_ymd_and_hms(2022, 8, 13, 12, 0, 0).unwrap(),
+            EphemerisVectorParser::parse(data.lines()).collect(),
+        )
+    }
+
+    #[test]
+    fn reading_source_kernels() {
+        let data = include_str!("orbital_elements.txt");
+        assert_eq!(vec!["DE441".to_string()], source_kernels(data));
+
+        let data = "Target body name: Jupiter (599)                   {source: jup365_merged}\n\
+                    Center body name: Sun (10)                        {source: DE441}";
+        assert_eq!(
+            vec!["jup365_merged".to_string(), "DE441".to_string()],
+            source_kernels(data)
+        );
+    }
+
+    #[test]
+    fn serializing_bundle() {
+        let bundle = vector_bundle();
+        assert!(!bundle.items.is_empty());
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let deserialized: ProvenanceBundle<EphemerisVectorItem<f32, DefaultUnits>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(bundle, deserialized);
+    }
+
+    #[cfg(feature = "si")]
+    #[test]
+    fn serializing_si_bundle() {
+        use crate::units::SiUnits;
+
+        let bundle = vector_bundle().map(EphemerisVectorItem::<f32, SiUnits>::from);
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let deserialized: ProvenanceBundle<EphemerisVectorItem<f32, SiUnits>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(bundle, deserialized);
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Place on Earth from which the ephemeris is observed, instead of the Sun's
+/// center.
+///
+/// <https://ssd.jpl.nasa.gov/horizons/manual.html#center>
+///
+/// Example
+/// ```
+/// # use rhorizons::Site;
+/// let backyard = Site::Geodetic {
+///     longitude: 19.94,
+///     latitude: 50.06,
+///     altitude: 0.22,
+/// };
+/// let palomar = Site::MpcCode("675".to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Site {
+    /// Geodetic coordinates on the WGS-84 reference ellipsoid
+    Geodetic {
+        /// East longitude in degrees
+        longitude: f64,
+        /// Latitude in degrees
+        latitude: f64,
+        /// Altitude above the reference ellipsoid in km
+        altitude: f64,
+    },
+    /// Observatory code assigned by the Minor Planet Center, e.g. "675" for
+    /// Palomar Mountain
+    ///
+    /// <https://minorplanetcenter.net/iau/lists/ObsCodesF.html>
+    MpcCode(String),
+}
+
+impl Site {
+    /// Horizons parameters selecting this site as a center.
+    pub(crate) fn parameters(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Site::Geodetic {
+                longitude,
+                latitude,
+                altitude,
+            } => vec![
+                // 399 is Earth, `coord` tells Horizons to take the site from
+                // the parameters below.
+                ("CENTER", "coord@399".to_string()),
+                ("COORD_TYPE", "GEODETIC".to_string()),
+                (
+                    "SITE_COORD",
+                    format!("{},{},{}", longitude, latitude, altitude),
+                ),
+            ],
+            Site::MpcCode(code) => vec![("CENTER", format!("{}@399", code))],
+        }
+    }
+}
+
+/// Named sites, e.g. "backyard" or "club-observatory", so they can be defined
+/// once and picked by name. Serializes as a plain map from names to sites, so
+/// it can be kept in a configuration file.
+///
+/// ```
+/// # use rhorizons::{Site, SiteRegistry};
+/// let mut sites = SiteRegistry::default();
+/// sites.insert("palomar", Site::MpcCode("675".to_string()));
+///
+/// assert_eq!(Some(&Site::MpcCode("675".to_string())), sites.get("palomar"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SiteRegistry {
+    sites: BTreeMap<String, Site>,
+}
+
+impl SiteRegistry {
+    /// Add a site, returning the one previously registered under `name`.
+    pub fn insert(&mut self, name: impl Into<String>, site: Site) -> Option<Site> {
+        self.sites.insert(name.into(), site)
+    }
+
+    /// Remove a site, returning it if it was registered.
+    pub fn remove(&mut self, name: &str) -> Option<Site> {
+        self.sites.remove(name)
+    }
+
+    /// Site registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&Site> {
+        self.sites.get(name)
+    }
+
+    /// All sites with their names, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Site)> {
+        self.sites.iter().map(|(name, site)| (name.as_str(), site))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializing_site_registry() {
+        let mut sites = SiteRegistry::default();
+        sites.insert(
+            "backyard",
+            Site::Geodetic {
+                longitude: 19.94,
+                latitude: 50.06,
+                altitude: 0.22,
+            },
+        );
+        sites.insert("palomar", Site::MpcCode("675".to_string()));
+
+        let json = serde_json::to_string(&sites).unwrap();
+        assert_eq!(
+            r#"{"backyard":{"Geodetic":{"longitude":19.94,"latitude":50.06,"altitude":0.22}},"palomar":{"MpcCode":"675"}}"#,
+            json
+        );
+        assert_eq!(sites, serde_json::from_str(&json).unwrap());
+
+        assert_eq!(
+            Some(Site::MpcCode("675".to_string())),
+            sites.remove("palomar")
+        );
+        assert_eq!(None, sites.get("palomar"));
+        assert_eq!(
+            vec!["backyard"],
+            sites.iter().map(|(name, _)| name).collect::<Vec<_>>()
+        );
+    }
+}
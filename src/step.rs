@@ -0,0 +1,162 @@
+use chrono::Duration;
+use std::{fmt, num::NonZeroU32};
+
+/// Interval between consecutive ephemeris entries, `STEP_SIZE` in Horizons.
+///
+/// <https://ssd.jpl.nasa.gov/horizons/manual.html#time>
+///
+/// ```
+/// # use rhorizons::Step;
+/// let step = Step::Minutes(10.try_into().unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Step given in minutes
+    Minutes(NonZeroU32),
+    /// Step given in hours
+    Hours(NonZeroU32),
+    /// Step given in days
+    Days(NonZeroU32),
+    /// Calendar step given in months, e.g. from 15th to 15th of next month
+    Months(NonZeroU32),
+    /// Calendar step given in years
+    Years(NonZeroU32),
+}
+
+const MINUTES_IN_HOUR: i64 = 60;
+const MINUTES_IN_DAY: i64 = 24 * MINUTES_IN_HOUR;
+
+impl Step {
+    /// Length of the step, `None` for calendar steps as months and years
+    /// differ in length.
+    pub fn duration(&self) -> Option<Duration> {
+        match *self {
+            Step::Minutes(n) => Some(Duration::minutes(n.get().into())),
+            Step::Hours(n) => Some(Duration::hours(n.get().into())),
+            Step::Days(n) => Some(Duration::days(n.get().into())),
+            Step::Months(_) | Step::Years(_) => None,
+        }
+    }
+
+    /// Smallest step for which the ephemeris spanning `span` does not exceed
+    /// `max_records` entries. Large steps are rounded up to whole hours or
+    /// days.
+    ///
+    /// Both ends of the span are always included, so there are at least 2
+    /// entries. `max_records` below 2 gives the same step as 2, i.e. the whole
+    /// span.
+    ///
+    /// ```
+    /// # use chrono::Duration;
+    /// # use rhorizons::Step;
+    /// assert_eq!(
+    ///     Step::Hours(6.try_into().unwrap()),
+    ///     Step::suggest(Duration::days(10), 41)
+    /// );
+    /// ```
+    pub fn suggest(span: Duration, max_records: u32) -> Step {
+        // Both ends of the span are included in the ephemeris.
+        let intervals = i64::from(max_records.saturating_sub(1).max(1));
+        let minutes = (span.num_minutes() + intervals - 1) / intervals;
+        Self::from_minutes(minutes, |minutes, unit| (minutes + unit - 1) / unit)
+    }
+
+    /// Largest step which still samples an orbit with period `period` at
+    /// least `samples_per_orbit` times. Large steps are rounded down to whole
+    /// hours or days.
+    ///
+    /// ```
+    /// # use chrono::Duration;
+    /// # use rhorizons::Step;
+    /// assert_eq!(
+    ///     Step::Days(3.try_into().unwrap()),
+    ///     Step::for_orbit(Duration::days(365), 100)
+    /// );
+    /// ```
+    pub fn for_orbit(period: Duration, samples_per_orbit: u32) -> Step {
+        let minutes = period.num_minutes() / i64::from(samples_per_orbit.max(1));
+        Self::from_minutes(minutes, |minutes, unit| minutes / unit)
+    }
+
+    fn from_minutes(minutes: i64, round: impl Fn(i64, i64) -> i64) -> Step {
+        let minutes = minutes.max(1);
+        let clamp = |n: i64| {
+            NonZeroU32::new(u32::try_from(n).unwrap_or(u32::MAX)).unwrap_or(NonZeroU32::MIN)
+        };
+
+        if minutes < MINUTES_IN_HOUR {
+            Step::Minutes(clamp(minutes))
+        } else if minutes < MINUTES_IN_DAY {
+            Step::Hours(clamp(round(minutes, MINUTES_IN_HOUR)))
+        } else {
+            Step::Days(clamp(round(minutes, MINUTES_IN_DAY)))
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Minutes(n) => write!(f, "{} m", n),
+            Step::Hours(n) => write!(f, "{} h", n),
+            Step::Days(n) => write!(f, "{} d", n),
+            Step::Months(n) => write!(f, "{} mo", n),
+            Step::Years(n) => write!(f, "{} y", n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(value: u32) -> NonZeroU32 {
+        NonZeroU32::new(value).unwrap()
+    }
+
+    #[test]
+    fn formatting_step() {
+        assert_eq!("10 m", Step::Minutes(n(10)).to_string());
+        assert_eq!("2 h", Step::Hours(n(2)).to_string());
+        assert_eq!("1 d", Step::Days(n(1)).to_string());
+        assert_eq!("1 mo", Step::Months(n(1)).to_string());
+        assert_eq!("2 y", Step::Years(n(2)).to_string());
+    }
+
+    #[test]
+    fn step_duration() {
+        assert_eq!(Some(Duration::minutes(10)), Step::Minutes(n(10)).duration());
+        assert_eq!(Some(Duration::days(2)), Step::Days(n(2)).duration());
+        assert_eq!(None, Step::Months(n(1)).duration());
+        assert_eq!(None, Step::Years(n(1)).duration());
+    }
+
+    #[test]
+    fn suggesting_step_for_record_count() {
+        assert_eq!(Step::Minutes(n(1)), Step::suggest(Duration::hours(1), 1000));
+        assert_eq!(Step::Minutes(n(15)), Step::suggest(Duration::hours(1), 5));
+        assert_eq!(Step::Minutes(n(20)), Step::suggest(Duration::hours(1), 4));
+        assert_eq!(Step::Hours(n(1)), Step::suggest(Duration::days(1), 25));
+        assert_eq!(Step::Hours(n(2)), Step::suggest(Duration::days(1), 24));
+        assert_eq!(Step::Days(n(2)), Step::suggest(Duration::days(365), 200));
+
+        // Fewer than 2 records are not possible, these give just both ends.
+        assert_eq!(Step::Days(n(10)), Step::suggest(Duration::days(10), 2));
+        assert_eq!(Step::Days(n(10)), Step::suggest(Duration::days(10), 1));
+        assert_eq!(Step::Days(n(10)), Step::suggest(Duration::days(10), 0));
+    }
+
+    #[test]
+    fn suggesting_step_for_orbit() {
+        assert_eq!(
+            Step::Minutes(n(1)),
+            Step::for_orbit(Duration::minutes(90), 1000)
+        );
+        assert_eq!(
+            Step::Minutes(n(9)),
+            Step::for_orbit(Duration::minutes(90), 10)
+        );
+        assert_eq!(Step::Hours(n(2)), Step::for_orbit(Duration::days(1), 10));
+        assert_eq!(Step::Days(n(27)), Step::for_orbit(Duration::days(27), 0));
+    }
+}
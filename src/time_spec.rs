@@ -0,0 +1,115 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Time range of an ephemeris query.
+///
+/// Provides shortcuts for common ranges relative to the current time.
+///
+/// ```
+/// # use rhorizons::TimeSpec;
+/// let span = TimeSpec::last_day();
+/// assert!(span.start < span.stop);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSpec {
+    /// Beginning of the range
+    pub start: DateTime<Utc>,
+    /// End of the range
+    pub stop: DateTime<Utc>,
+}
+
+impl TimeSpec {
+    /// Range between two arbitrary timestamps.
+    pub fn new(start: DateTime<Utc>, stop: DateTime<Utc>) -> Self {
+        Self { start, stop }
+    }
+
+    /// Last 24 hours, ending now.
+    pub fn last_day() -> Self {
+        Self::last_days(1)
+    }
+
+    /// Last `n` days, ending now. Saturates at the earliest representable
+    /// time.
+    pub fn last_days(n: u32) -> Self {
+        let now = Utc::now();
+        Self::new(earlier(now, Duration::days(n.into())), now)
+    }
+
+    /// Next `n` days, starting now. Saturates at the latest representable
+    /// time.
+    pub fn next_days(n: u32) -> Self {
+        let now = Utc::now();
+        Self::new(now, later(now, Duration::days(n.into())))
+    }
+
+    /// Range spanning `window` before and after the `epoch`. Negative window
+    /// is treated as positive one and the range saturates at the earliest and
+    /// latest representable times.
+    pub fn around(epoch: DateTime<Utc>, window: Duration) -> Self {
+        let (start, stop) = (earlier(epoch, window), later(epoch, window));
+        Self::new(start.min(stop), start.max(stop))
+    }
+
+    /// Length of the range.
+    pub fn duration(&self) -> Duration {
+        self.stop - self.start
+    }
+}
+
+fn earlier(time: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    time.checked_sub_signed(duration)
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+fn later(time: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    time.checked_add_signed(duration)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn time_spec_around_epoch() {
+        let epoch = Utc.with_ymd_and_hms(2022, 8, 13, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            TimeSpec {
+                start: Utc.with_ymd_and_hms(2022, 8, 13, 10, 0, 0).unwrap(),
+                stop: Utc.with_ymd_and_hms(2022, 8, 13, 14, 0, 0).unwrap(),
+            },
+            TimeSpec::around(epoch, Duration::hours(2))
+        );
+        assert_eq!(
+            TimeSpec::around(epoch, Duration::hours(2)),
+            TimeSpec::around(epoch, Duration::hours(-2))
+        );
+    }
+
+    #[test]
+    fn time_spec_saturates() {
+        let epoch = Utc.with_ymd_and_hms(2022, 8, 13, 12, 0, 0).unwrap();
+        assert_eq!(
+            TimeSpec {
+                start: DateTime::<Utc>::MIN_UTC,
+                stop: DateTime::<Utc>::MAX_UTC,
+            },
+            TimeSpec::around(epoch, Duration::days(1_000_000_000))
+        );
+
+        assert_eq!(DateTime::<Utc>::MAX_UTC, TimeSpec::next_days(u32::MAX).stop);
+        assert_eq!(
+            DateTime::<Utc>::MIN_UTC,
+            TimeSpec::last_days(u32::MAX).start
+        );
+    }
+
+    #[test]
+    fn time_spec_relative_to_now() {
+        assert_eq!(Duration::days(1), TimeSpec::last_day().duration());
+        assert_eq!(Duration::days(3), TimeSpec::next_days(3).duration());
+        assert!(TimeSpec::last_day().stop <= TimeSpec::next_days(1).start);
+    }
+}
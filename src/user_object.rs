@@ -0,0 +1,99 @@
+/// Heliocentric ecliptic (J2000) osculating elements of an object which is not
+/// known to Horizons, e.g. a newly discovered asteroid or comet with your own
+/// orbit solution. Horizons integrates these elements and produces ephemeris
+/// just like for any other body.
+///
+/// <https://ssd-api.jpl.nasa.gov/doc/horizons.html>
+///
+/// | Horizons Symbol | Meaning                     | Unit                |
+/// |-----------------|-----------------------------|---------------------|
+/// | EPOCH           | Epoch of the elements       | Julian Day (TDB)    |
+/// | EC              | Eccentricity                |                     |
+/// | QR              | Perihelion distance         | au                  |
+/// | TP              | Time of perihelion          | Julian Day (TDB)    |
+/// | OM              | Longitude of Ascending Node | degrees             |
+/// | W               | Argument of perihelion      | degrees             |
+/// | IN              | Inclination                 | degrees             |
+///
+/// Example
+/// ```
+/// # use rhorizons::HeliocentricElements;
+/// let elements = HeliocentricElements {
+///     epoch: 2449600.5,
+///     eccentricity: 0.6,
+///     perihelion_distance: 1.2,
+///     time_of_perihelion: 2449700.5,
+///     longitude_of_ascending_node: 90.0,
+///     argument_of_perihelion: 30.0,
+///     inclination: 15.0,
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeliocentricElements {
+    /// Epoch (Julian Day Number, TDB) at which the elements are valid
+    pub epoch: f64,
+    /// Eccentricity of the orbit
+    pub eccentricity: f64,
+    /// Perihelion distance in astronomical units (au)
+    pub perihelion_distance: f64,
+    /// Time of perihelion passage (Julian Day Number, TDB)
+    pub time_of_perihelion: f64,
+    /// Longitude of the ascending node in degrees
+    pub longitude_of_ascending_node: f64,
+    /// Argument of perihelion in degrees
+    pub argument_of_perihelion: f64,
+    /// Inclination in degrees
+    pub inclination: f64,
+}
+
+impl HeliocentricElements {
+    /// Horizons parameters selecting this object as a target.
+    pub(crate) fn parameters(&self) -> Vec<(&'static str, String)> {
+        vec![
+            // Semicolon alone tells Horizons that the object is defined by
+            // the parameters below instead of being looked up.
+            ("COMMAND", ";".to_string()),
+            ("ECLIP", "J2000".to_string()),
+            ("EPOCH", self.epoch.to_string()),
+            ("EC", self.eccentricity.to_string()),
+            ("QR", self.perihelion_distance.to_string()),
+            ("TP", self.time_of_perihelion.to_string()),
+            ("OM", self.longitude_of_ascending_node.to_string()),
+            ("W", self.argument_of_perihelion.to_string()),
+            ("IN", self.inclination.to_string()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_object_parameters() {
+        let elements = HeliocentricElements {
+            epoch: 2449600.5,
+            eccentricity: 0.6,
+            perihelion_distance: 1.2,
+            time_of_perihelion: 2449700.5,
+            longitude_of_ascending_node: 90.0,
+            argument_of_perihelion: 30.0,
+            inclination: 15.0,
+        };
+
+        assert_eq!(
+            vec![
+                ("COMMAND", ";".to_string()),
+                ("ECLIP", "J2000".to_string()),
+                ("EPOCH", "2449600.5".to_string()),
+                ("EC", "0.6".to_string()),
+                ("QR", "1.2".to_string()),
+                ("TP", "2449700.5".to_string()),
+                ("OM", "90".to_string()),
+                ("W", "30".to_string()),
+                ("IN", "15".to_string()),
+            ],
+            elements.parameters()
+        );
+    }
+}
@@ -23,6 +23,17 @@ pub fn take_expecting<'a>(value: &'a str, expected: &str) -> Result<&'a str, Tak
     }
 }
 
+/// Pairs of labels and values from a line like `X_s= 1.1E+00 Y_s=-2.0E-01`,
+/// regardless of their widths.
+pub fn labelled_values(line: &str) -> impl Iterator<Item = (&str, &str)> {
+    let parts: Vec<&str> = line.split('=').collect();
+    (1..parts.len()).filter_map(move |n| {
+        let label = parts[n - 1].split_whitespace().last()?;
+        let value = parts[n].split_whitespace().next()?;
+        Some((label, value))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,6 +46,16 @@ mod tests {
         assert_eq!(("ab", ""), take_or_empty("ab", 4));
     }
 
+    #[test]
+    fn test_labelled_values() {
+        assert_eq!(
+            vec![("X_s", "1.1E+00"), ("Y_s", "-2.0E-01"), ("Z", "3")],
+            labelled_values(" X_s= 1.1E+00 Y_s=-2.0E-01 Z = 3").collect::<Vec<_>>()
+        );
+        assert_eq!(0, labelled_values("").count());
+        assert_eq!(0, labelled_values("X_s=").count());
+    }
+
     #[test]
     fn test_take_expecting() {
         assert_eq!(Ok("b"), take_expecting("ab", "a"));
@@ -7,6 +7,13 @@ fn init() {
     let _ = env_logger::builder().is_test(true).try_init();
 }
 
+fn time_span() -> TimeSpec {
+    TimeSpec::new(
+        Utc.with_ymd_and_hms(2016, 10, 15, 12, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2016, 10, 15, 13, 0, 0).unwrap(),
+    )
+}
+
 #[tokio::test]
 async fn finding_earth() {
     init();
@@ -54,3 +61,98 @@ async fn getting_jupiter_ephemeris() {
 
     assert_eq!(-8.125930353044792E+08, vectors[0].position[0]);
 }
+
+#[tokio::test]
+async fn getting_user_object_ephemeris() {
+    init();
+
+    let elements = HeliocentricElements {
+        epoch: 2449600.5,
+        eccentricity: 0.6,
+        perihelion_distance: 1.2,
+        time_of_perihelion: 2449700.5,
+        longitude_of_ascending_node: 90.0,
+        argument_of_perihelion: 30.0,
+        inclination: 15.0,
+    };
+
+    let vectors = EphemerisQuery::new(elements, time_span()).vectors().await;
+
+    assert!(!vectors.items.is_empty());
+}
+
+#[tokio::test]
+async fn getting_ephemeris_with_provenance() {
+    init();
+
+    let bundle = EphemerisQuery::new(399, time_span()).vectors().await;
+
+    assert!(bundle
+        .parameters
+        .contains(&("COMMAND".to_string(), "399".to_string())));
+    assert!(!bundle.source_kernels.is_empty());
+    assert!(!bundle.items.is_empty());
+}
+
+#[tokio::test]
+async fn getting_ephemeris_with_step() {
+    init();
+
+    let vectors = EphemerisQuery::new(399, time_span())
+        .with_step(Step::Minutes(10.try_into().unwrap()))
+        .vectors()
+        .await;
+
+    assert_eq!(7, vectors.items.len());
+}
+
+#[tokio::test]
+async fn searching_major_bodies() {
+    init();
+
+    let bodies = search_major_bodies("mar").await;
+    assert!(bodies.iter().any(|body| body.id == 499));
+
+    let bodies = search_major_bodies("Phobos").await;
+    assert_eq!(401, bodies[0].id);
+}
+
+#[tokio::test]
+async fn getting_barycenter_ephemeris() {
+    init();
+
+    let bodies = major_bodies().await;
+    for barycenter in Barycenter::ALL {
+        assert!(bodies
+            .iter()
+            .any(|body| body.barycenter() == Some(barycenter)));
+    }
+
+    let vectors = ephemeris_vector(
+        Barycenter::Jupiter.id(),
+        Utc.with_ymd_and_hms(2016, 10, 15, 12, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2016, 10, 15, 13, 0, 0).unwrap(),
+    )
+    .await;
+
+    assert!(!vectors.is_empty());
+}
+
+#[tokio::test]
+async fn getting_ephemeris_progressively() {
+    init();
+
+    let mut updates = Vec::new();
+    let vectors = EphemerisQuery::new(399, time_span())
+        .vectors_progressive(
+            [
+                Step::Minutes(30.try_into().unwrap()),
+                Step::Minutes(10.try_into().unwrap()),
+            ],
+            |items| updates.push(items.len()),
+        )
+        .await;
+
+    assert_eq!(vec![3, 7], updates);
+    assert_eq!(7, vectors.len());
+}